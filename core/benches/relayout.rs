@@ -0,0 +1,92 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use mocha_core::{
+    component::{Component, Components, Layer},
+    layout::{Dimensions, Size},
+    tree::Node,
+};
+
+const SCREEN: Dimensions = Dimensions {
+    width: 800.0,
+    height: 600.0,
+};
+
+/// Builds a layer of 10 * 10 * 100 leaves under three levels of containers, 10,111 nodes in all.
+/// The containers, and every other leaf, are sized relative to the screen, so a resize lays out
+/// much of the tree again
+fn layer() -> Layer {
+    let mut root = Node::new(Component::new());
+    root.data.layout.set_size(Size::Fill, Size::Fill);
+    for _ in 0..10 {
+        let mut section = Node::new(Component::new());
+        section
+            .data
+            .layout
+            .set_size(Size::Percent(10.0), Size::Fill);
+        for _ in 0..10 {
+            let mut group = Node::new(Component::new());
+            group.data.layout.set_size(Size::Fill, Size::Percent(50.0));
+            for i in 0..100 {
+                let mut leaf = Node::new(Component::new());
+                if i % 2 == 0 {
+                    leaf.data.layout.set_size(Size::Percent(1.0), Size::Fill);
+                }
+                group.push_child(leaf);
+            }
+            section.push_child(group);
+        }
+        root.push_child(section);
+    }
+
+    Layer {
+        component_tree: root,
+    }
+}
+
+fn laid_out() -> Components {
+    let mut components = Components::new();
+    components.layers.push(layer());
+    components.compute_layout(SCREEN);
+    components
+}
+
+fn mark_all(node: &mut Node<Component>) {
+    node.data.relayout();
+    for child in &mut node.children {
+        mark_all(child);
+    }
+}
+
+fn relayout(c: &mut Criterion) {
+    let mut components = laid_out();
+    c.bench_function("relayout whole tree", |b| {
+        b.iter(|| {
+            mark_all(&mut components.layers[0].component_tree);
+            components.compute_layout(SCREEN);
+        })
+    });
+
+    let mut components = laid_out();
+    c.bench_function("relayout one leaf", |b| {
+        b.iter(|| {
+            components.layers[0].component_tree.children[5].children[5].children[50]
+                .data
+                .relayout();
+            components.compute_layout(SCREEN);
+        })
+    });
+
+    let mut components = laid_out();
+    let mut wide = false;
+    c.bench_function("resize", |b| {
+        b.iter(|| {
+            wide = !wide;
+            components.compute_layout(Dimensions {
+                width: if wide { 1024.0 } else { SCREEN.width },
+                height: SCREEN.height,
+            });
+        })
+    });
+}
+
+criterion_group!(benches, relayout);
+criterion_main!(benches);
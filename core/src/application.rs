@@ -1,10 +1,9 @@
-use std::collections::VecDeque;
-
 use crate::{
     component::{Component, Components, Layer},
-    event::Event,
-    layout::{ComputedLayout, Dimensions, Offset, Orientation, Position, Size},
-    style::{BorderRadius, Colour, StyleSheet},
+    event::{Event, KeyboardEvent, MouseButton, PointerEvent, WindowEvent},
+    layout::{ComputedLayout, Dimensions, Offset, Orientation, Position, Rect, Size},
+    stacking::PaintOrder,
+    style::{BorderRadius, Colour, ComputedStyle, Theme},
     tree::Node,
     window::{Window, WindowConfig},
     Quad, Renderer,
@@ -19,32 +18,34 @@ pub struct Application<R: Renderer, W: Window> {
 impl<R: Renderer, W: Window> Application<R, W> {
     pub fn new() -> Self {
         let window = W::new(WindowConfig::default());
-        let renderer = window.init_renderer();
+        let renderer: R = window.init_renderer();
+        let (width, height) = window.get_size();
+        renderer.update_viewport(width, height, window.get_scale_factor());
         let mut components = Components::new();
 
         let mut root_comp = Node::new(Component::new());
         root_comp.data.layout.width = Size::Fill;
         root_comp.data.layout.height = Size::Fill;
-        root_comp.data.style.background_colour = Colour::new(1.0, 0.5, 0.0);
+        root_comp.data.style.background_colour = Some(Colour::new(1.0, 0.5, 0.0));
 
         let mut child_1 = Node::new(Component::new());
         child_1.data.layout.position = Position::Absolute(
             Offset::new(0.0, 100.0),
             crate::layout::Anchor::Top,
         );
-        child_1.data.style.background_colour = Colour::new(0.0, 0.5, 0.0);
+        child_1.data.style.background_colour = Some(Colour::new(0.0, 0.5, 0.0));
         child_1.data.layout.height = Size::Constant(50.0);
         child_1.data.layout.width = Size::Constant(50.0);
-        child_1.data.style.border_radius = BorderRadius::new(5.0, 20.0, 5.0, 20.0);
+        child_1.data.style.border_radius = Some(BorderRadius::new(5.0, 20.0, 5.0, 20.0));
 
 
         let mut child_2 = Node::new(Component::new());
-        child_2.data.style.background_colour = Colour::new(1.0, 0.5, 0.5);
+        child_2.data.style.background_colour = Some(Colour::new(1.0, 0.5, 0.5));
         child_2.data.layout.height = Size::Constant(50.0);
         child_2.data.layout.width = Size::Fill;
 
         let mut child_3 = Node::new(Component::new());
-        child_3.data.style.background_colour = Colour::new(0.5, 0.0, 0.0);
+        child_3.data.style.background_colour = Some(Colour::new(0.5, 0.0, 0.0));
         child_3.data.layout.height = Size::Fill;
         child_3.data.layout.width = Size::Constant(50.0);
  
@@ -67,28 +68,80 @@ impl<R: Renderer, W: Window> Application<R, W> {
         // if pointer event, run hit test?
 
         // else, push into tree (components)
+
+        match event {
+            Event::PointerEvent(PointerEvent::MouseWheel {
+                x,
+                y,
+                delta_x,
+                delta_y,
+            }) => {
+                self.components.scroll(x, y, delta_x, delta_y);
+            }
+            Event::PointerEvent(PointerEvent::MouseMoved { x, y }) => {
+                self.components.pointer_moved(x, y);
+            }
+            Event::PointerEvent(PointerEvent::MouseExited) => {
+                self.components.pointer_left();
+            }
+            Event::PointerEvent(PointerEvent::MouseInput {
+                x,
+                y,
+                button: MouseButton::Left,
+                pressed,
+            }) => {
+                self.components.pointer_input(x, y, pressed);
+            }
+            Event::KeyboardEvent(KeyboardEvent::KeyboardInput {
+                key,
+                pressed,
+                shift,
+            }) => {
+                self.components.key_input(key, pressed, shift);
+            }
+            Event::WindowEvent(WindowEvent::Resized {
+                width,
+                height,
+                scale_factor,
+            }) => {
+                self.renderer.update_viewport(width, height, scale_factor);
+            }
+            _ => {}
+        }
+    }
+
+    /// Switches the theme of every component, such as between light and dark mode, which is applied
+    /// when the next frame is drawn
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.components.set_theme(theme);
+    }
+
+    /// Returns whether any component has changed since the last frame was drawn
+    pub fn needs_redraw(&mut self) -> bool {
+        self.components.is_dirty()
     }
 
     pub fn draw(&mut self) {
         let window_size = self.window.get_size();
+        let scale_factor = self.window.get_scale_factor();
 
-        self.components.compute_layout(Dimensions {
-            width: window_size.0 as f32,
-            height: window_size.1 as f32,
-        });
+        // layout is in logical pixels, which are only snapped to physical pixels when painted
+        self.components.compute_layout(Dimensions::from_physical(
+            window_size.0,
+            window_size.1,
+            scale_factor,
+        ));
 
         let layer = &self.components.layers[0];
 
-        let mut to_visit = VecDeque::new();
-        to_visit.push_back(&layer.component_tree);
-
-        while !to_visit.is_empty() {
-            let node = to_visit.pop_front().unwrap();
-            self.renderer
-                .draw(create_quad(&node.data.style, &node.data.computed_layout));
-            for child in &node.children {
-                to_visit.push_back(child)
-            }
+        // paint by stacking context, so that each component is painted over those below it
+        for painted in PaintOrder::new(&layer.component_tree).iter() {
+            self.renderer.draw(create_quad(
+                &painted.node.data.computed_style,
+                &painted.node.data.computed_layout,
+                &painted.clip,
+                scale_factor,
+            ));
         }
 
         // for (style, layout) in layer.style.iter().zip(layer.layout.iter()) {
@@ -140,30 +193,56 @@ impl<R: Renderer, W: Window> Application<R, W> {
         // });
 
         self.renderer.present();
+        self.components.mark_painted();
     }
 }
 
-pub fn create_quad(style: &StyleSheet, layout: &ComputedLayout) -> Quad {
+/// Creates the quad for the given component, where its edges are snapped to the physical pixels of
+/// the given scale factor
+pub fn create_quad(
+    style: &ComputedStyle,
+    layout: &ComputedLayout,
+    clip: &Rect,
+    scale_factor: f32,
+) -> Quad {
+    let rect = layout.rect().snapped(scale_factor);
+    let clip = clip.snapped(scale_factor);
+    let radius = &style.border_radius;
+    // the left corners are at the start, which is on the right when laid out from right to left
+    let (top_right, bottom_right, bottom_left, top_left) = match layout.direction.is_rtl() {
+        true => (
+            radius.top_left,
+            radius.bottom_left,
+            radius.bottom_right,
+            radius.top_right,
+        ),
+        false => (
+            radius.top_right,
+            radius.bottom_right,
+            radius.bottom_left,
+            radius.top_left,
+        ),
+    };
+
     Quad {
-        position: [layout.position.x, layout.position.y],
-        dimensions: [layout.width, layout.height],
+        position: [rect.x, rect.y],
+        dimensions: [rect.width, rect.height],
         colour: [
             style.background_colour.r,
             style.background_colour.g,
             style.background_colour.b,
+            style.background_colour.a,
         ],
         opacity: style.opacity,
         border_colour: [
             style.border_colour.r,
             style.border_colour.g,
             style.border_colour.b,
+            style.border_colour.a,
         ],
-        border_radius: [
-            style.border_radius.top_right,
-            style.border_radius.bottom_right,
-            style.border_radius.bottom_left,
-            style.border_radius.top_left,
-        ],
-        border_weight: style.border_weight,
+        border_radius: [top_right, bottom_right, bottom_left, top_left],
+        // a percentage is of the width of the component
+        border_weight: style.border_weight.resolve(layout.width, &layout.units),
+        clip: [clip.x, clip.y, clip.width, clip.height],
     }
 }
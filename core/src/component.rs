@@ -1,6 +1,17 @@
+use std::fmt::Write;
+
 use crate::{
-    layout::{ComputedLayout, Dimensions, Layout, Orientation, Padding, Point, Position, Size},
-    style::{StyleSheet, LIGHT},
+    constraints,
+    diagnostics::{self, Diagnostic},
+    event::Key,
+    grid,
+    layout::{
+        Align, Anchor, ComputedLayout, Dimensions, Direction, FlowLine, Layout, Length, Overflow,
+        Point, Position, Rect, Size, Units, Wrap,
+    },
+    rules::{MatchedRules, Rules},
+    stacking::{HitRegions, PaintOrder},
+    style::{ComputedStyle, StateStyles, Style, StyleSheet, Theme, LIGHT},
     tree::Node,
 };
 
@@ -10,261 +21,3558 @@ pub struct Layer {
     pub component_tree: Node<Component>,
 }
 
+impl Layer {
+    /// Returns the computed layout of the tree as indented text, one component to a line, giving its
+    /// id, or otherwise its index amongst its siblings, followed by its position and size, e.g.
+    ///
+    /// ```text
+    /// #root (0, 0) 800x600
+    ///   [0] (0, 0) 300x200
+    ///   #sidebar (300, 0) 100x600
+    /// ```
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        Self::dump_node(&self.component_tree, 0, 0, &mut dump);
+        dump
+    }
+
+    fn dump_node(node: &Node<Component>, index: usize, depth: usize, dump: &mut String) {
+        let computed = &node.data.computed_layout;
+        // adding zero turns negative zero into zero, so that both are written the same
+        let _ = writeln!(
+            dump,
+            "{:indent$}{} ({}, {}) {}x{}",
+            "",
+            node.data.label(index),
+            computed.position.x + 0.0,
+            computed.position.y + 0.0,
+            computed.width + 0.0,
+            computed.height + 0.0,
+            indent = depth * 2
+        );
+
+        for (i, child) in node.children.iter().enumerate() {
+            Self::dump_node(child, i, depth + 1, dump);
+        }
+    }
+}
+
 pub struct Components {
     pub layers: Vec<Layer>,
+    /// the theme whose style sheet every component starts from, before its own overrides and
+    /// anything it inherits
+    theme: Theme,
+    /// the rules that apply to the components they match, over the style sheet of the theme
+    rules: Rules,
+    /// the font size that `Rem` lengths are multiples of
+    root_font_size: f32,
+    /// the problems found with the last computed layout, in debug builds
+    diagnostics: Vec<Diagnostic>,
+    /// the component under the pointer, which its ancestors are hovered along with
+    hovered: Option<Target>,
+    /// the component being pressed, by the pointer or from the keyboard while focused, which its
+    /// ancestors are pressed along with
+    pressed: Option<Target>,
+    /// the component that keyboard input is directed to
+    focused: Option<Target>,
+    /// where the components of each layer can be hit, as of the last layout, or `None` until it is
+    /// next needed
+    hit_regions: Vec<Option<HitRegions>>,
 }
 
+/// A component, as the index of its layer and the path to it from the root of that layer, as the index
+/// of each component amongst the children of its parent
+type Target = (usize, Vec<usize>);
+
 impl Components {
     pub fn new() -> Self {
-        Self { layers: Vec::new() }
+        Self {
+            layers: Vec::new(),
+            theme: Theme::light(),
+            rules: Rules::default(),
+            root_font_size: 16.0,
+            diagnostics: Vec::new(),
+            hovered: None,
+            pressed: None,
+            focused: None,
+            hit_regions: Vec::new(),
+        }
     }
 
-    pub fn compute_layout(&mut self, screen: Dimensions) {
-        // for now just rebuild the entire tree based on current state of components
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Switches the theme that every component is styled against. Each layer is restyled with the next
+    /// layout, where whatever changed is painted again, or laid out again when its font changed
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        for layer in &mut self.layers {
+            Self::restyle_all(&mut layer.component_tree);
+        }
+    }
+
+    /// Marks the given node, and all of its descendants, to be restyled
+    fn restyle_all(comp_tree: &mut Node<Component>) {
+        comp_tree.data.restyle();
+        for child in &mut comp_tree.children {
+            Self::restyle_all(child);
+        }
+    }
+
+    /// Replaces the rules applied to the components they match. Each layer is restyled with the next
+    /// layout, where layout properties given by the rules apply to every component they match that
+    /// leaves those properties as they were initially
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
         for layer in &mut self.layers {
-            // establish root for computed layout
+            Self::forget_rules(&mut layer.component_tree);
+        }
+    }
+
+    /// Forgets which rules the given node, and all of its descendants, matched, so they are applied
+    /// again even if the same rules match
+    fn forget_rules(comp_tree: &mut Node<Component>) {
+        comp_tree.data.matched_rules = None;
+        comp_tree.data.restyle();
+        for child in &mut comp_tree.children {
+            Self::forget_rules(child);
+        }
+    }
+
+    /// Sets the font size that `Rem` lengths are multiples of, such as the font size preferred by the
+    /// user. Anything given in `Rem` is laid out again with the next layout
+    pub fn set_root_font_size(&mut self, font_size: f32) {
+        self.root_font_size = font_size;
+    }
+
+    /// Checks the computed layout of every layer for likely mistakes, such as children overflowing
+    /// their parent, or sizes that are negative or NaN
+    pub fn diagnose(&self) -> Vec<Diagnostic> {
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, layer)| diagnostics::diagnose(i, &layer.component_tree))
+            .collect()
+    }
+
+    /// Returns the problems found with the layout when it was last computed. These are only looked
+    /// for in debug builds, where each is also logged as a warning when it is first found
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns the computed layout of every layer as indented text, under a heading for each layer.
+    /// See `Layer::dump`
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let _ = writeln!(dump, "layer {i}");
+            Layer::dump_node(&layer.component_tree, 0, 1, &mut dump);
+        }
+        dump
+    }
+
+    /// Lays out every layer against the given screen size.
+    /// Only the parts of each tree that were marked dirty, or whose size or position changed as a
+    /// result, are recomputed
+    pub fn compute_layout(&mut self, screen: Dimensions) {
+        // layers that were removed take where they could be hit with them
+        self.hit_regions.truncate(self.layers.len());
+        self.validate_targets();
+
+        let mut laid_out = false;
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            // the passes below find their way down to whatever was marked, and keep the marks on the
+            // ancestors of anything they mark themselves
+            Self::propagate_dirty(&mut layer.component_tree);
+            Self::resolve_styles(
+                &mut layer.component_tree,
+                None,
+                false,
+                self.theme.style_sheet(),
+                &self.rules,
+            );
+            // every node shares the screen and root font size, while a node whose own font size changed
+            // was marked to be laid out along with it, so otherwise only dirty nodes are visited
+            let root_units = &layer.component_tree.data.computed_layout.units;
+            let all = root_units.root_font_size != self.root_font_size
+                || root_units.viewport_width != screen.width
+                || root_units.viewport_height != screen.height;
+            Self::resolve_units(
+                &mut layer.component_tree,
+                self.root_font_size,
+                &screen,
+                all,
+                &self.rules,
+            );
+            let dirty = layer.component_tree.data.dirty;
+
+            // intrinsic sizes are needed before any `FitContent` element can be sized top-down
+            Self::measure(&mut layer.component_tree);
+
+            // establish root for computed layout, where the screen is the box it is positioned in
+            let screen = Rect {
+                x: 0.0,
+                y: 0.0,
+                width: screen.width,
+                height: screen.height,
+            };
             let root_component = &mut layer.component_tree.data;
-            root_component.computed_layout.position = match &root_component.layout.position {
-                Position::Static => Point { x: 0.0, y: 0.0 },
-                Position::Relative(offset) => Point {
-                    x: offset.x,
-                    y: offset.y,
-                },
-                Position::Absolute(offset, anchor) => {
-                    let anchor_point = match anchor {
-                        crate::layout::Anchor::Top => Point {
-                            x: screen.width / 2.0,
-                            y: 0.0,
-                        },
-                        crate::layout::Anchor::TopRight => Point {
-                            x: screen.width,
-                            y: 0.0,
-                        },
-                        crate::layout::Anchor::Right => Point {
-                            x: screen.width,
-                            y: screen.height / 2.0,
-                        },
-                        crate::layout::Anchor::BottomRight => Point {
-                            x: screen.width,
-                            y: screen.height,
-                        },
-                        crate::layout::Anchor::Bottom => Point {
-                            x: screen.width / 2.0,
-                            y: screen.height,
-                        },
-                        crate::layout::Anchor::BottomLeft => Point {
-                            x: 0.0,
-                            y: screen.height,
-                        },
-                        crate::layout::Anchor::Left => Point {
-                            x: 0.0,
-                            y: screen.height / 2.0,
-                        },
-                        crate::layout::Anchor::TopLeft => Point { x: 0.0, y: 0.0 },
-                    };
-                    Point {
-                        x: anchor_point.x + offset.x,
-                        y: anchor_point.y + offset.y,
-                    }
+            let previous = root_component.computed_layout.rect();
+
+            root_component.computed_layout.width = match root_component.effective_layout.width {
+                Size::Constant(size) => size,
+                Size::Percent(percent) => screen.width * percent / 100.0,
+                Size::Length(length) => {
+                    length.resolve(screen.width, &root_component.computed_layout.units)
                 }
+                Size::Fill | Size::Weighted(_) => screen.width,
+                Size::FitContent => root_component.computed_layout.intrinsic_width,
             };
+            root_component.computed_layout.width = root_component
+                .effective_layout
+                .clamp_width(root_component.computed_layout.width);
 
-            root_component.computed_layout.width = match root_component.layout.width {
+            root_component.computed_layout.height = match root_component.effective_layout.height {
                 Size::Constant(size) => size,
-                Size::Fill => screen.width,
+                Size::Percent(percent) => screen.height * percent / 100.0,
+                Size::Length(length) => {
+                    length.resolve(screen.height, &root_component.computed_layout.units)
+                }
+                Size::Fill | Size::Weighted(_) => screen.height,
+                Size::FitContent => root_component.computed_layout.intrinsic_height,
             };
+            root_component.computed_layout.height =
+                match root_component.effective_layout.aspect_ratio {
+                    Some(ratio) => root_component.computed_layout.width / ratio,
+                    None => root_component.computed_layout.height,
+                };
+            root_component.computed_layout.height = root_component
+                .effective_layout
+                .clamp_height(root_component.computed_layout.height);
 
-            root_component.computed_layout.height = match root_component.layout.height {
-                Size::Constant(size) => size,
-                Size::Fill => screen.height,
+            root_component.computed_layout.direction = root_component
+                .effective_layout
+                .direction
+                .unwrap_or_default();
+            let position = match &root_component.effective_layout.position {
+                Position::Static | Position::Sticky(_) => Point { x: 0.0, y: 0.0 },
+                Position::Relative(offset) => {
+                    let (x, y) = offset.resolve(
+                        screen.width,
+                        screen.height,
+                        &root_component.computed_layout.units,
+                    );
+                    Point { x, y }
+                }
+                Position::Absolute(_, _) => {
+                    Self::resolve_absolute_position(&layer.component_tree, &screen)
+                }
             };
+            let root_component = &mut layer.component_tree.data;
+            root_component.computed_layout.position = position;
+            if root_component.computed_layout.direction.is_rtl()
+                && !root_component.effective_layout.position.is_absolute()
+            {
+                root_component.computed_layout.position.x = screen.mirror_x(
+                    root_component.computed_layout.position.x,
+                    root_component.computed_layout.width,
+                );
+            }
+
+            // the root is the only node that depends on the screen directly, so if it has not moved
+            // a resize leaves a clean tree alone
+            let moved = root_component.computed_layout.rect() != previous;
+            if moved {
+                root_component.dirty.paint = true;
+            }
+            // anything laid out or painted again is hit against its new box from now on
+            if moved || root_component.dirty.needs_layout() || dirty.needs_paint() {
+                if let Some(regions) = self.hit_regions.get_mut(i) {
+                    *regions = None;
+                }
+            }
+            if !moved && !root_component.dirty.needs_layout() {
+                continue;
+            }
+            laid_out = true;
+
+            // the root is the containing block of its absolutely positioned descendants, unless
+            // another positioned element is nearer to them
+            let containing = root_component.computed_layout.content_box();
+            let scrollport = root_component.computed_layout.scrollport();
+            Self::compute_child_layouts(&mut layer.component_tree, &containing, &scrollport);
+        }
+
+        // problems are logged only when they are first found, and can only be found once something
+        // is laid out again
+        if cfg!(debug_assertions) && laid_out {
+            let diagnostics = self.diagnose();
+            for diagnostic in &diagnostics {
+                if !self.diagnostics.contains(diagnostic) {
+                    log::warn!("layout: {diagnostic}");
+                }
+            }
+            self.diagnostics = diagnostics;
+        }
+    }
+
+    /// Returns whether anything in any layer needs to be laid out or painted again
+    pub fn is_dirty(&mut self) -> bool {
+        self.layers.iter_mut().any(|layer| {
+            let dirty = Self::propagate_dirty(&mut layer.component_tree);
+            dirty.needs_style() || dirty.needs_layout() || dirty.needs_paint()
+        })
+    }
 
-            Self::resolve_child_dimensions(&mut layer.component_tree);
-            Self::resolve_child_positions(&mut layer.component_tree);
-            Self::compute_child_layouts(&mut layer.component_tree);
+    /// Clears the paint flags of every layer, once it has been painted
+    pub fn mark_painted(&mut self) {
+        for layer in &mut self.layers {
+            Self::clear_paint(&mut layer.component_tree);
         }
     }
 
-    fn compute_child_layouts(comp_tree: &mut Node<Component>) {
-        for node in &mut comp_tree.children {
-            Self::resolve_child_dimensions(node);
-            Self::resolve_child_positions(node);
+    /// Cascades the style overrides of the given node, and all of its descendants, over the rules they
+    /// match, the computed style of their parent and the style sheet. Only nodes that were marked to be
+    /// restyled, or whose parent's computed style changed, are cascaded again.
+    /// Nodes whose computed style changed are marked to be painted again, or laid out again when their
+    /// font changed, as that may change their content. Nodes that match different rules than before
+    /// are laid out again, so that their effective layout follows the rules they match
+    fn resolve_styles(
+        comp_tree: &mut Node<Component>,
+        parent: Option<&ComputedStyle>,
+        parent_changed: bool,
+        style_sheet: &StyleSheet,
+        rules: &Rules,
+    ) {
+        let dirty = comp_tree.data.dirty;
+        if !parent_changed && !dirty.needs_style() {
+            return;
+        }
+        let changed = (parent_changed || dirty.style)
+            && Self::cascade(&mut comp_tree.data, parent, style_sheet, rules);
+        comp_tree.data.dirty.style = false;
+        comp_tree.data.dirty.descendant_style = false;
 
-            Self::compute_child_layouts(node)
+        for child in &mut comp_tree.children {
+            Self::resolve_styles(
+                child,
+                Some(&comp_tree.data.computed_style),
+                changed,
+                style_sheet,
+                rules,
+            );
+            comp_tree.data.dirty.include(child.data.dirty);
         }
     }
 
-    /// Resolves the screen position of the children of the given node
-    /// This process is contigent on the width/height and position of the given node
-    fn resolve_child_positions(comp_tree: &mut Node<Component>) {
-        if comp_tree.has_no_children() {
+    /// Cascades the style of a single component, returning whether its computed style changed
+    fn cascade(
+        component: &mut Component,
+        parent: Option<&ComputedStyle>,
+        style_sheet: &StyleSheet,
+        rules: &Rules,
+    ) -> bool {
+        // rules are only matched again once the tag, classes or id they were matched against change
+        let current = component.matched_rules.as_ref();
+        if !current.is_some_and(|matched| matched.is_current(component)) {
+            let matched = MatchedRules::new(rules, component);
+            let previous = component.matched_rules.as_ref().map(|m| &m.indices);
+            if previous != Some(&matched.indices) {
+                component.dirty.layout = true;
+            }
+            component.matched_rules = Some(matched);
+        }
+
+        let overrides = match &component.matched_rules {
+            Some(matched) => component
+                .state_style()
+                .or(&component.style)
+                .or(&matched.style),
+            None => component.state_style().or(&component.style),
+        };
+        let style = ComputedStyle::cascade(&overrides, parent, style_sheet);
+        let previous = &component.computed_style;
+
+        if style == *previous {
+            return false;
+        }
+        if style.font_size != previous.font_size || style.font_weight != previous.font_weight {
+            component.dirty.layout = true;
+        }
+        component.dirty.paint = true;
+        component.computed_style = style;
+        true
+    }
+
+    /// Resolves the units that the relative lengths of the given node, and all of its descendants, are
+    /// given in, or only those that are to be laid out unless `all` is set. Nodes whose lengths resolve
+    /// to different sizes than before, such as those given in `Vw` after a resize, are marked to be
+    /// laid out, or painted, again. Nodes that are to be laid out have their effective layout rebuilt
+    /// from their own layout and the layout properties of the rules they match
+    fn resolve_units(
+        comp_tree: &mut Node<Component>,
+        root_font_size: f32,
+        screen: &Dimensions,
+        all: bool,
+        rules: &Rules,
+    ) {
+        if !all && !comp_tree.data.dirty.needs_layout() {
             return;
         }
 
-        let parent_orient = &comp_tree.data.layout.orientation;
-        let parent_pos = &comp_tree.data.computed_layout.position;
-        let parent_w = comp_tree.data.computed_layout.width;
-        let parent_h = comp_tree.data.computed_layout.height;
-        let mut flow_offset_x = 0.0;
-        let mut flow_offset_y = 0.0;
+        let component = &mut comp_tree.data;
+        if component.dirty.layout {
+            component.effective_layout = component.layout.clone();
+            if let Some(matched) = &component.matched_rules {
+                rules.apply_layout(
+                    &matched.indices,
+                    &component.layout,
+                    &mut component.effective_layout,
+                );
+            }
+        }
+        let units = Units {
+            font_size: component.computed_style.font_size,
+            root_font_size,
+            viewport_width: screen.width,
+            viewport_height: screen.height,
+        };
+        let previous = component.computed_layout.units;
+
+        if units != previous {
+            if component.effective_layout.depends_on(&previous, &units) {
+                component.dirty.layout = true;
+            }
+            let border_weight = &component.computed_style.border_weight;
+            if border_weight.resolve(0.0, &previous) != border_weight.resolve(0.0, &units) {
+                component.dirty.paint = true;
+            }
+            component.computed_layout.units = units;
+        }
+        // the padding may also have been changed along with marking the node to be laid out
+        if component.dirty.layout {
+            component.computed_layout.padding = component.effective_layout.padding.resolve(&units);
+        }
 
         for child in &mut comp_tree.children {
-            child.data.computed_layout.position = match &child.data.layout.position {
-                Position::Static => match parent_orient {
-                    Orientation::Row => {
-                        let p = Point {
-                            x: parent_pos.x + flow_offset_x,
-                            y: parent_pos.y,
-                        };
-                        flow_offset_x += child.data.computed_layout.width;
-                        p
-                    }
-                    Orientation::Column => {
-                        let p = Point {
-                            x: parent_pos.x,
-                            y: parent_pos.y + flow_offset_y,
-                        };
-                        flow_offset_y += child.data.computed_layout.height;
-                        p
-                    }
-                },
-                Position::Relative(offset) => match parent_orient {
-                    Orientation::Row => {
-                        let p = Point {
-                            x: parent_pos.x + offset.x + flow_offset_x,
-                            y: parent_pos.y + offset.y,
-                        };
-                        flow_offset_x += child.data.computed_layout.width;
-                        p
-                    }
-                    Orientation::Column => {
-                        let p = Point {
-                            x: parent_pos.x + offset.x,
-                            y: parent_pos.y + offset.y + flow_offset_y,
-                        };
-                        flow_offset_y += child.data.computed_layout.height;
-                        p
-                    }
-                },
-                Position::Absolute(offset, anchor) => {
-                    let anchor_point = match anchor {
-                        crate::layout::Anchor::Top => Point {
-                            x: parent_pos.x + (parent_w - child.data.computed_layout.width) / 2.0,
-                            y: parent_pos.y,
-                        },
-                        crate::layout::Anchor::TopRight => Point {
-                            x: parent_pos.x + parent_w - child.data.computed_layout.width,
-                            y: parent_pos.y,
-                        },
-                        crate::layout::Anchor::Right => Point {
-                            x: parent_pos.x + parent_w - child.data.computed_layout.width,
-                            y: parent_pos.y + (parent_h - child.data.computed_layout.height) / 2.0,
-                        },
-                        crate::layout::Anchor::BottomRight => Point {
-                            x: parent_pos.x + parent_w - child.data.computed_layout.width,
-                            y: parent_pos.y + parent_h - child.data.computed_layout.height,
-                        },
-                        crate::layout::Anchor::Bottom => Point {
-                            x: parent_pos.x + (parent_w - child.data.computed_layout.width) / 2.0,
-                            y: parent_pos.y + parent_h - child.data.computed_layout.height,
-                        },
-                        crate::layout::Anchor::BottomLeft => Point {
-                            x: parent_pos.x,
-                            y: parent_pos.y + parent_h - child.data.computed_layout.height,
-                        },
-                        crate::layout::Anchor::Left => Point {
-                            x: parent_pos.x,
-                            y: parent_pos.y + (parent_h - child.data.computed_layout.height) / 2.0,
-                        },
-                        crate::layout::Anchor::TopLeft => parent_pos.clone(),
-                    };
-                    Point {
-                        x: anchor_point.x + offset.x,
-                        y: anchor_point.y + offset.y,
-                    }
-                }
-            };
+            Self::resolve_units(child, root_font_size, screen, all, rules);
+            comp_tree.data.dirty.include(child.data.dirty);
         }
     }
 
-    /// Resolves the width and height of the children of the given node.
-    /// This process is contigent on the width/height of the given node
-    fn resolve_child_dimensions(comp_tree: &mut Node<Component>) {
-        if comp_tree.has_no_children() {
+    /// Marks every ancestor of a dirty node as having dirty descendants, so that the layout and paint
+    /// passes can find their way down to it. Returns the state of the given node
+    fn propagate_dirty(comp_tree: &mut Node<Component>) -> Dirty {
+        for child in &mut comp_tree.children {
+            let dirty = Self::propagate_dirty(child);
+            comp_tree.data.dirty.include(dirty);
+        }
+        comp_tree.data.dirty
+    }
+
+    fn clear_paint(comp_tree: &mut Node<Component>) {
+        let dirty = &mut comp_tree.data.dirty;
+        if !dirty.needs_paint() {
             return;
         }
+        dirty.paint = false;
+        dirty.descendant_paint = false;
+
+        for child in &mut comp_tree.children {
+            Self::clear_paint(child);
+        }
+    }
 
-        let parent_width = comp_tree.data.computed_layout.width;
-        let parent_height = comp_tree.data.computed_layout.height;
-        let mut children_total_const_w = 0.0;
-        let mut children_total_const_h = 0.0;
-        let mut children_fill_w_count = 0.0;
-        let mut children_fill_h_count = 0.0;
+    /// Scrolls the innermost scroll container under the given point by the given delta, in pixels,
+    /// where positive values scroll towards the start of the content. Whatever it can't scroll is
+    /// passed on to the scroll containers around it.
+    ///
+    /// Returns whether the point is over any layer
+    pub fn scroll(&mut self, x: f32, y: f32, delta_x: f32, delta_y: f32) -> bool {
+        let mut delta = (delta_x, delta_y);
 
-        for child in &comp_tree.children {
-            if let Position::Absolute(_, _) = child.data.layout.position {
-                continue;
+        let Some((layer, path)) = self.hit_test(x, y) else {
+            return false;
+        };
+        Self::scroll_along(&mut self.layers[layer].component_tree, &path, &mut delta);
+        true
+    }
+
+    /// Scrolls the node at the end of the given path from the given node by the delta, and then each
+    /// of its ancestors by what is left of it, innermost first. The path ends early at the deepest node
+    /// that still exists, as the regions hit may be older than the tree
+    fn scroll_along(comp_tree: &mut Node<Component>, path: &[usize], delta: &mut (f32, f32)) {
+        if let Some((child, path)) = path.split_first() {
+            if let Some(child) = comp_tree.children.get_mut(*child) {
+                Self::scroll_along(child, path, delta);
             }
+        }
+
+        if matches!(comp_tree.data.effective_layout.overflow, Overflow::Scroll) {
+            let computed = &mut comp_tree.data.computed_layout;
+            let (max_x, max_y) = computed.max_scroll();
+            let scroll_x = (computed.scroll.x - delta.0).clamp(0.0, max_x);
+            let scroll_y = (computed.scroll.y - delta.1).clamp(0.0, max_y);
+
+            delta.0 -= computed.scroll.x - scroll_x;
+            delta.1 -= computed.scroll.y - scroll_y;
+            computed.scroll = Point {
+                x: scroll_x,
+                y: scroll_y,
+            };
+            // the children have to be moved to follow the new offset
+            comp_tree.data.dirty.layout = true;
+        }
+    }
+
+    /// Returns the topmost component under the given point, in any layer. Each layer is only ordered
+    /// for hit testing once after it is laid out or painted again
+    fn hit_test(&mut self, x: f32, y: f32) -> Option<Target> {
+        self.hit_regions.resize_with(self.layers.len(), || None);
+
+        // later layers are painted on top, so are hit first
+        (0..self.layers.len()).rev().find_map(|i| {
+            let layer = &self.layers[i];
+            let regions = self.hit_regions[i]
+                .get_or_insert_with(|| PaintOrder::new(&layer.component_tree).hit_regions());
+            Some((i, regions.hit_test(x, y)?))
+        })
+    }
+
+    /// Moves the pointer to the given point, where the component under it is hovered along with its
+    /// ancestors
+    pub fn pointer_moved(&mut self, x: f32, y: f32) {
+        let hovered = self.hit_test(x, y);
+        self.retarget(State::Hovered, hovered);
+    }
+
+    /// Moves the pointer out of the window, so nothing is hovered
+    pub fn pointer_left(&mut self) {
+        self.retarget(State::Hovered, None);
+    }
 
-            match &child.data.layout.width {
-                Size::Constant(size) => children_total_const_w += size,
-                Size::Fill => children_fill_w_count += 1.0,
+    /// Presses, or releases, the pointer at the given point. The component under it is pressed along
+    /// with its ancestors until it is released, and the nearest of them that is focusable takes focus,
+    /// or focus is lost when none are
+    pub fn pointer_input(&mut self, x: f32, y: f32, pressed: bool) {
+        if !pressed {
+            self.retarget(State::Pressed, None);
+            return;
+        }
+
+        let target = self.hit_test(x, y);
+        let focused = target.as_ref().and_then(|(layer, path)| {
+            let mut node = &self.layers[*layer].component_tree;
+            let mut focusable = node.data.can_focus().then_some(0);
+            for (depth, child) in path.iter().enumerate() {
+                // the regions hit may be older than the tree
+                node = node.children.get(*child)?;
+                if node.data.can_focus() {
+                    focusable = Some(depth + 1);
+                }
             }
+            focusable.map(|depth| (*layer, path[..depth].to_vec()))
+        });
+        self.retarget(State::Pressed, target);
+        self.retarget(State::Focused, focused);
+    }
 
-            match &child.data.layout.height {
-                Size::Constant(size) => children_total_const_h += size,
-                Size::Fill => children_fill_h_count += 1.0,
+    /// Presses, or releases, the given key. `Tab` moves focus to the next focusable component, or the
+    /// previous one with shift held, while `Enter` and `Space` press the focused component until they
+    /// are released
+    pub fn key_input(&mut self, key: Key, pressed: bool, shift: bool) {
+        match key {
+            Key::Tab if pressed => self.move_focus(shift),
+            Key::Enter | Key::Space => {
+                let target = if pressed { self.focused.clone() } else { None };
+                self.retarget(State::Pressed, target);
             }
+            _ => {}
         }
+    }
 
-        let (children_auto_width, children_auto_height) = match &comp_tree.data.layout.orientation {
-            Orientation::Row => (
-                (parent_width - children_total_const_w) / children_fill_w_count,
-                parent_height,
-            ),
-            Orientation::Column => (
-                parent_width,
-                (parent_height - children_total_const_h) / children_fill_h_count,
-            ),
+    /// Moves focus to the next focusable component in the order they are defined, or the previous one
+    /// when going backwards, wrapping around at either end
+    fn move_focus(&mut self, backwards: bool) {
+        let mut focusable = Vec::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            Self::collect_focusable(&layer.component_tree, i, &mut Vec::new(), &mut focusable);
+        }
+        if focusable.is_empty() {
+            return;
+        }
+
+        let count = focusable.len();
+        let current = self
+            .focused
+            .as_ref()
+            .and_then(|focused| focusable.iter().position(|target| target == focused));
+        let next = match current {
+            Some(i) if backwards => (i + count - 1) % count,
+            Some(i) => (i + 1) % count,
+            None if backwards => count - 1,
+            None => 0,
         };
+        self.retarget(State::Focused, Some(focusable.swap_remove(next)));
+    }
+
+    fn collect_focusable(
+        comp_tree: &Node<Component>,
+        layer: usize,
+        path: &mut Vec<usize>,
+        focusable: &mut Vec<Target>,
+    ) {
+        if comp_tree.data.can_focus() {
+            focusable.push((layer, path.clone()));
+        }
+        for (i, child) in comp_tree.children.iter().enumerate() {
+            path.push(i);
+            Self::collect_focusable(child, layer, path, focusable);
+            path.pop();
+        }
+    }
+
+    /// Drops each state whose component is no longer where it was found, such as after the tree changed
+    /// shape, along with focus on a component that can no longer take it, so that no component is left
+    /// in a state it can't be taken out of
+    fn validate_targets(&mut self) {
+        for state in [State::Hovered, State::Pressed, State::Focused] {
+            let current = match state {
+                State::Hovered => &mut self.hovered,
+                State::Pressed => &mut self.pressed,
+                State::Focused => &mut self.focused,
+            };
+            let Some((layer, path)) = current.as_ref() else {
+                continue;
+            };
+            let node = self.layers.get(*layer).and_then(|layer| {
+                path.iter().try_fold(&layer.component_tree, |node, child| {
+                    node.children.get(*child)
+                })
+            });
+            let valid = node.is_some_and(|node| {
+                node.data.interaction.get(state)
+                    && (!matches!(state, State::Focused) || node.data.can_focus())
+            });
+            if valid {
+                continue;
+            }
 
+            // the components in the state may have moved anywhere, so it is cleared from all of them
+            *current = None;
+            for layer in &mut self.layers {
+                Self::clear_state(&mut layer.component_tree, state);
+            }
+        }
+    }
+
+    /// Takes the given node, and all of its descendants, out of the given state
+    fn clear_state(comp_tree: &mut Node<Component>, state: State) {
+        let component = &mut comp_tree.data;
+        let flag = component.interaction.get_mut(state);
+        if *flag {
+            *flag = false;
+            component.restyle();
+        }
         for child in &mut comp_tree.children {
-            match &child.data.layout.width {
-                Size::Constant(size) => child.data.computed_layout.width = *size,
-                Size::Fill => child.data.computed_layout.width = children_auto_width,
+            Self::clear_state(child, state);
+        }
+    }
+
+    /// Moves the given state to another component, or to none. Components that enter or leave the state
+    /// are marked to be restyled, and so painted again if their style changes
+    fn retarget(&mut self, state: State, target: Option<Target>) {
+        let current = match state {
+            State::Hovered => &mut self.hovered,
+            State::Pressed => &mut self.pressed,
+            State::Focused => &mut self.focused,
+        };
+        if *current == target {
+            return;
+        }
+
+        let previous = std::mem::replace(current, target.clone());
+        for (target, value) in [(previous, false), (target, true)] {
+            let Some((layer, path)) = target else {
+                continue;
+            };
+            // the layers may have changed since the target was found
+            if let Some(layer) = self.layers.get_mut(layer) {
+                Self::set_state(&mut layer.component_tree, &path, state, value);
+            }
+        }
+    }
+
+    /// Sets whether the node at the end of the given path from the given node is in the given state,
+    /// along with each of its ancestors unless the state is being focused
+    fn set_state(comp_tree: &mut Node<Component>, path: &[usize], state: State, value: bool) {
+        if path.is_empty() || !matches!(state, State::Focused) {
+            let component = &mut comp_tree.data;
+            let flag = component.interaction.get_mut(state);
+            if *flag != value {
+                *flag = value;
+                component.restyle();
             }
+        }
 
-            match &child.data.layout.height {
-                Size::Constant(size) => child.data.computed_layout.height = *size,
-                Size::Fill => child.data.computed_layout.height = children_auto_height,
+        if let Some((child, path)) = path.split_first() {
+            if let Some(child) = comp_tree.children.get_mut(*child) {
+                Self::set_state(child, path, state, value);
             }
         }
     }
-}
 
-pub struct Component {
-    pub style: StyleSheet,
-    pub layout: Layout,
-    pub computed_layout: ComputedLayout,
-    // pub connections: Vec<Box<dyn Msg>>
-}
+    /// Resolves the intrinsic width and height of the given node, and all of its descendants, from the
+    /// bottom up.
+    /// This is the size the node would take if it were only as large as its content, which is either
+    /// given by its measure hook or derived from the intrinsic sizes of its flowed children
+    /// Only dirty nodes, and the ancestors of dirty nodes, are measured again, as the intrinsic size of
+    /// a clean subtree can't have changed
+    fn measure(comp_tree: &mut Node<Component>) {
+        if !comp_tree.data.dirty.needs_layout() {
+            return;
+        }
 
-impl Component {
-    pub fn new() -> Self {
-        Self {
-            style: LIGHT,
-            layout: Layout {
-                orientation: Orientation::Row,
-                position: Position::Static,
-                height: Size::Constant(200.0),
-                width: Size::Constant(300.0),
-                padding: Padding::none(),
+        for child in &mut comp_tree.children {
+            Self::measure(child);
+        }
+
+        let layout = &comp_tree.data.effective_layout;
+        let (content_width, content_height) = match &comp_tree.data.measure {
+            Some(measure) => {
+                let content = measure(&comp_tree.data);
+                (content.width, content.height)
+            }
+            None if layout.orientation.is_grid() => grid::measure_content(comp_tree),
+            None if layout.orientation.is_constraints() => constraints::measure_content(comp_tree),
+            None if layout.orientation.is_stack() => {
+                let mut width: f32 = 0.0;
+                let mut height: f32 = 0.0;
+                for child in &comp_tree.children {
+                    if child.data.effective_layout.position.is_absolute() {
+                        continue;
+                    }
+
+                    let margin = &child.data.effective_layout.margin;
+                    width =
+                        width.max(margin.horizontal() + child.data.computed_layout.intrinsic_width);
+                    height =
+                        height.max(margin.vertical() + child.data.computed_layout.intrinsic_height);
+                }
+                (width, height)
+            }
+            None => {
+                let is_row = layout.orientation.is_row();
+                let mut main = 0.0;
+                let mut cross: f32 = 0.0;
+                let mut flow_count = 0.0;
+
+                for child in &comp_tree.children {
+                    if child.data.effective_layout.position.is_absolute() {
+                        continue;
+                    }
+
+                    let margin = &child.data.effective_layout.margin;
+                    let width = margin.horizontal() + child.data.computed_layout.intrinsic_width;
+                    let height = margin.vertical() + child.data.computed_layout.intrinsic_height;
+                    let (child_main, child_cross) = match is_row {
+                        true => (width, height),
+                        false => (height, width),
+                    };
+
+                    main += child_main;
+                    cross = cross.max(child_cross);
+                    flow_count += 1.0;
+                }
+                main += layout.gap * f32::max(flow_count - 1.0, 0.0);
+
+                match is_row {
+                    true => (main, cross),
+                    false => (cross, main),
+                }
+            }
+        };
+
+        let computed = &mut comp_tree.data.computed_layout;
+        computed.intrinsic_width = layout.clamp_width(match layout.width {
+            Size::Constant(size) => size,
+            Size::Length(length) if !matches!(length, Length::Percent(_)) => {
+                length.resolve(0.0, &computed.units)
+            }
+            // relative sizes can't be known until the parent is sized, so fall back to the content
+            Size::Percent(_)
+            | Size::Fill
+            | Size::Weighted(_)
+            | Size::FitContent
+            | Size::Length(_) => content_width + computed.padding.horizontal(),
+        });
+        computed.intrinsic_height = match layout.aspect_ratio {
+            Some(ratio) => computed.intrinsic_width / ratio,
+            None => match layout.height {
+                Size::Constant(size) => size,
+                Size::Length(length) if !matches!(length, Length::Percent(_)) => {
+                    length.resolve(0.0, &computed.units)
+                }
+                Size::Percent(_)
+                | Size::Fill
+                | Size::Weighted(_)
+                | Size::FitContent
+                | Size::Length(_) => content_height + computed.padding.vertical(),
             },
-            computed_layout: ComputedLayout::default(),
+        };
+        computed.intrinsic_height = layout.clamp_height(computed.intrinsic_height);
+    }
+
+    /// Lays out the children of the given node, and then every child that is dirty, has dirty
+    /// descendants, was moved or resized, or was given a different containing block or direction.
+    /// Children that were moved or resized are marked to be painted again.
+    /// `containing` is the containing block given by the nearest positioned ancestor, and `scrollport`
+    /// the box given by the nearest scroll container
+    fn compute_child_layouts(
+        comp_tree: &mut Node<Component>,
+        containing: &Rect,
+        scrollport: &Rect,
+    ) {
+        let direction = comp_tree.data.computed_layout.direction;
+        let previous: Vec<(Rect, Direction)> = comp_tree
+            .children
+            .iter_mut()
+            .map(|child| {
+                let computed = &mut child.data.computed_layout;
+                let previous = (computed.rect(), computed.direction);
+                computed.direction = child.data.effective_layout.direction.unwrap_or(direction);
+                previous
+            })
+            .collect();
+
+        comp_tree.data.computed_layout.containing_block = *containing;
+        comp_tree.data.computed_layout.scrollport = *scrollport;
+        Self::resolve_child_dimensions(comp_tree);
+        Self::resolve_absolute_dimensions(comp_tree, &Self::containing_block(comp_tree));
+        Self::resolve_child_positions(comp_tree);
+        // the scroll offset of a positioned node is only settled once its children are positioned
+        let containing = Self::containing_block(comp_tree);
+        Self::resolve_absolute_positions(comp_tree, &containing);
+        let scrollport = Self::scrollport(comp_tree);
+        Self::resolve_sticky_positions(comp_tree, &scrollport);
+
+        for (node, (previous, previous_direction)) in comp_tree.children.iter_mut().zip(previous) {
+            let computed = &node.data.computed_layout;
+            if computed.rect() != previous || computed.direction != previous_direction {
+                node.data.dirty.paint = true;
+                comp_tree.data.dirty.descendant_paint = true;
+            } else if !node.data.dirty.needs_layout()
+                && computed.containing_block == containing
+                && computed.scrollport == scrollport
+            {
+                continue;
+            }
+
+            Self::compute_child_layouts(node, &containing, &scrollport)
+        }
+
+        comp_tree.data.dirty.layout = false;
+        comp_tree.data.dirty.descendant_layout = false;
+    }
+
+    /// Returns the box the absolutely positioned children of the given node are placed in, which is
+    /// its own content box when it is positioned, or otherwise the one it was given by its ancestors
+    fn containing_block(comp_tree: &Node<Component>) -> Rect {
+        let component = &comp_tree.data;
+        match component.effective_layout.position.is_positioned() {
+            true => component.computed_layout.content_box(),
+            false => component.computed_layout.containing_block,
+        }
+    }
+
+    /// Returns the box the sticky children of the given node are kept within, which is its own
+    /// scrollport when it is a scroll container, or otherwise the one it was given by its ancestors
+    fn scrollport(comp_tree: &Node<Component>) -> Rect {
+        let component = &comp_tree.data;
+        match component.effective_layout.overflow {
+            Overflow::Scroll => component.computed_layout.scrollport(),
+            _ => component.computed_layout.scrollport,
+        }
+    }
+
+    /// Moves the sticky children of the given node, already placed in the flow, so that they are not
+    /// scrolled past their offset from the start of the given scrollport on the axes it is given on,
+    /// while remaining within the content of the node
+    fn resolve_sticky_positions(comp_tree: &mut Node<Component>, scrollport: &Rect) {
+        let computed = &comp_tree.data.computed_layout;
+        let content = computed.content_box();
+        // the content of a scroll container extends past its content box, towards the end
+        let content_width = content.width.max(computed.content_width);
+        let content_left = content.x + content.width - content_width;
+        let content_right = content.x + content_width;
+        let content_bottom = content.y + content.height.max(computed.content_height);
+        let is_rtl = computed.direction.is_rtl();
+
+        for child in &mut comp_tree.children {
+            let Position::Sticky(offset) = &child.data.effective_layout.position else {
+                continue;
+            };
+
+            let units = &child.data.computed_layout.units;
+            let offset_x = offset.x.map(|x| x.resolve(scrollport.width, units));
+            let offset_y = offset.y.map(|y| y.resolve(scrollport.height, units));
+            let computed = &mut child.data.computed_layout;
+            // the start is on the right when laid out from right to left
+            if let Some(offset_x) = offset_x {
+                computed.position.x = match is_rtl {
+                    true => computed.position.x.min(f32::max(
+                        scrollport.x + scrollport.width - offset_x - computed.width,
+                        content_left,
+                    )),
+                    false => computed.position.x.max(f32::min(
+                        scrollport.x + offset_x,
+                        content_right - computed.width,
+                    )),
+                };
+            }
+            if let Some(offset_y) = offset_y {
+                computed.position.y = computed.position.y.max(f32::min(
+                    scrollport.y + offset_y,
+                    content_bottom - computed.height,
+                ));
+            }
         }
     }
 
-    pub fn repaint(&self, env: &mut Environment) {}
+    /// Resolves the width and height of the absolutely positioned children of the given node against
+    /// their containing block, as if it were the cross axis of a `Row` or `Column`
+    fn resolve_absolute_dimensions(comp_tree: &mut Node<Component>, containing: &Rect) {
+        for child in &mut comp_tree.children {
+            let layout = &child.data.effective_layout;
+            if !layout.position.is_absolute() {
+                continue;
+            }
+
+            let computed = &mut child.data.computed_layout;
+            computed.width = layout.clamp_width(Self::resolve_cross_size(
+                &layout.width,
+                containing.width,
+                layout.margin.horizontal(),
+                computed.intrinsic_width,
+                false,
+                &computed.units,
+            ));
+            computed.height = layout.clamp_height(match layout.aspect_ratio {
+                Some(ratio) => computed.width / ratio,
+                None => Self::resolve_cross_size(
+                    &layout.height,
+                    containing.height,
+                    layout.margin.vertical(),
+                    computed.intrinsic_height,
+                    false,
+                    &computed.units,
+                ),
+            });
+        }
+    }
 
-    pub fn relayout(&self, env: &mut Environment) {}
+    /// Resolves the screen position of the absolutely positioned children of the given node, each
+    /// anchored within their containing block
+    fn resolve_absolute_positions(comp_tree: &mut Node<Component>, containing: &Rect) {
+        for child in &mut comp_tree.children {
+            if child.data.effective_layout.position.is_absolute() {
+                child.data.computed_layout.position =
+                    Self::resolve_absolute_position(child, containing);
+            }
+        }
+    }
+
+    /// Resolves the screen position of the children of the given node
+    /// This process is contigent on the width/height, position and padding of the given node, as well
+    /// as its scroll offset when it is a scroll container.
+    /// Children are always placed from left to right, and then mirrored within the node when it is
+    /// laid out from right to left
+    fn resolve_child_positions(comp_tree: &mut Node<Component>) {
+        if comp_tree.has_no_children() {
+            return;
+        }
+
+        if comp_tree.data.effective_layout.orientation.is_grid() {
+            grid::resolve_child_positions(comp_tree);
+        } else if comp_tree.data.effective_layout.orientation.is_constraints() {
+            constraints::resolve_child_positions(comp_tree);
+        } else if comp_tree.data.effective_layout.orientation.is_stack() {
+            Self::resolve_stack_positions(comp_tree);
+        } else {
+            Self::resolve_flow_positions(comp_tree);
+        }
+
+        Self::resolve_scroll(comp_tree);
+
+        if comp_tree.data.computed_layout.direction.is_rtl() {
+            let parent_rect = comp_tree.data.computed_layout.rect();
+            for child in &mut comp_tree.children {
+                let computed = &mut child.data.computed_layout;
+                if !child.data.effective_layout.position.is_absolute() {
+                    computed.position.x = parent_rect.mirror_x(computed.position.x, computed.width);
+                }
+            }
+        }
+    }
+
+    /// Measures the extent of the content of the given node, and when it is a scroll container, clamps
+    /// its scroll offset to that extent and shifts its children by it.
+    /// Since descendants are positioned relative to their parent, shifting the children is enough to
+    /// shift everything within them. Absolutely positioned children are left out, as they are placed
+    /// in their containing block, which only moves with the content when it is this node
+    fn resolve_scroll(comp_tree: &mut Node<Component>) {
+        let parent_padding = &comp_tree.data.computed_layout.padding;
+        let computed = &comp_tree.data.computed_layout;
+        let origin_x = computed.position.x + parent_padding.left;
+        let origin_y = computed.position.y + parent_padding.top;
+
+        let mut content_width: f32 = 0.0;
+        let mut content_height: f32 = 0.0;
+        for child in &comp_tree.children {
+            if child.data.effective_layout.position.is_absolute() {
+                continue;
+            }
+
+            let margin = &child.data.effective_layout.margin;
+            let child_layout = &child.data.computed_layout;
+            content_width = content_width
+                .max(child_layout.position.x + child_layout.width + margin.right - origin_x);
+            content_height = content_height
+                .max(child_layout.position.y + child_layout.height + margin.bottom - origin_y);
+        }
+
+        let computed = &mut comp_tree.data.computed_layout;
+        computed.content_width = content_width;
+        computed.content_height = content_height;
+
+        if !matches!(comp_tree.data.effective_layout.overflow, Overflow::Scroll) {
+            computed.scroll = Point::default();
+            return;
+        }
+
+        let (max_x, max_y) = computed.max_scroll();
+        computed.scroll.x = computed.scroll.x.clamp(0.0, max_x);
+        computed.scroll.y = computed.scroll.y.clamp(0.0, max_y);
+
+        let scroll = computed.scroll.clone();
+        for child in &mut comp_tree.children {
+            if child.data.effective_layout.position.is_absolute() {
+                continue;
+            }
+
+            child.data.computed_layout.position.x -= scroll.x;
+            child.data.computed_layout.position.y -= scroll.y;
+        }
+    }
+
+    /// Resolves the screen position of the children of the given row or column node
+    /// This process is contigent on the width/height, position and padding of the given node, as well
+    /// as the lines its children were broken into when their dimensions were resolved
+    fn resolve_flow_positions(comp_tree: &mut Node<Component>) {
+        let is_row = comp_tree.data.effective_layout.orientation.is_row();
+        let parent_padding = &comp_tree.data.computed_layout.padding;
+        // children are placed within the content box, i.e. the parent's box less its padding
+        let parent_pos = &Point {
+            x: comp_tree.data.computed_layout.position.x + parent_padding.left,
+            y: comp_tree.data.computed_layout.position.y + parent_padding.top,
+        };
+        let parent_w = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+        let parent_h = comp_tree.data.computed_layout.height - parent_padding.vertical();
+        let parent_gap = comp_tree.data.effective_layout.gap;
+        let parent_align = &comp_tree.data.effective_layout.align;
+        let parent_main = if is_row { parent_w } else { parent_h };
+        let mut line_offset = 0.0;
+
+        for line in &comp_tree.data.computed_layout.lines {
+            let children = &mut comp_tree.children[line.start..line.end];
+
+            // space along the main axis that is not taken up by flowed children, which is then
+            // distributed according to the justification of the parent
+            let mut flow_count = 0.0;
+            let mut flow_used = 0.0;
+            for child in children.iter() {
+                if child.data.effective_layout.position.is_absolute() {
+                    continue;
+                }
+
+                let margin = &child.data.effective_layout.margin;
+                flow_count += 1.0;
+                flow_used += match is_row {
+                    true => margin.horizontal() + child.data.computed_layout.width,
+                    false => margin.vertical() + child.data.computed_layout.height,
+                };
+            }
+            flow_used += parent_gap * f32::max(flow_count - 1.0, 0.0);
+            let (mut flow_offset, flow_spacing) = comp_tree
+                .data
+                .effective_layout
+                .justify
+                .distribute(parent_main - flow_used, flow_count);
+
+            for child in children.iter_mut() {
+                let margin = &child.data.effective_layout.margin;
+                let align = child
+                    .data
+                    .effective_layout
+                    .align_self
+                    .as_ref()
+                    .unwrap_or(parent_align);
+                let width = child.data.computed_layout.width;
+                let height = child.data.computed_layout.height;
+
+                // absolutely positioned children are placed in their containing block afterwards
+                let (offset_x, offset_y) = match &child.data.effective_layout.position {
+                    Position::Static | Position::Sticky(_) => (0.0, 0.0),
+                    Position::Relative(offset) => {
+                        offset.resolve(parent_w, parent_h, &child.data.computed_layout.units)
+                    }
+                    Position::Absolute(_, _) => continue,
+                };
+
+                // children are aligned within their own line, which spans the whole parent when
+                // not wrapping
+                child.data.computed_layout.position = match is_row {
+                    true => {
+                        let p = Point {
+                            x: parent_pos.x + offset_x + flow_offset + margin.left,
+                            y: parent_pos.y
+                                + offset_y
+                                + line_offset
+                                + align.offset(line.cross_size, height, margin.top, margin.bottom),
+                        };
+                        flow_offset += margin.horizontal() + width + parent_gap + flow_spacing;
+                        p
+                    }
+                    false => {
+                        let p = Point {
+                            x: parent_pos.x
+                                + offset_x
+                                + line_offset
+                                + align.offset(line.cross_size, width, margin.left, margin.right),
+                            y: parent_pos.y + offset_y + flow_offset + margin.top,
+                        };
+                        flow_offset += margin.vertical() + height + parent_gap + flow_spacing;
+                        p
+                    }
+                };
+            }
+
+            line_offset += line.cross_size + comp_tree.data.effective_layout.line_gap;
+        }
+    }
+
+    /// Resolves the screen position of an absolutely positioned child, anchored within the given
+    /// containing block
+    fn resolve_absolute_position(child: &Node<Component>, containing: &Rect) -> Point {
+        let width = child.data.computed_layout.width;
+        let height = child.data.computed_layout.height;
+        let centre_x = containing.x + (containing.width - width) / 2.0;
+        let centre_y = containing.y + (containing.height - height) / 2.0;
+        let right = containing.x + containing.width - width;
+        let bottom = containing.y + containing.height - height;
+
+        match &child.data.effective_layout.position {
+            Position::Absolute(offset, anchor) => {
+                let anchor_point = match anchor {
+                    Anchor::Centre => Point {
+                        x: centre_x,
+                        y: centre_y,
+                    },
+                    Anchor::Top => Point {
+                        x: centre_x,
+                        y: containing.y,
+                    },
+                    Anchor::TopRight => Point {
+                        x: right,
+                        y: containing.y,
+                    },
+                    Anchor::Right => Point {
+                        x: right,
+                        y: centre_y,
+                    },
+                    Anchor::BottomRight => Point {
+                        x: right,
+                        y: bottom,
+                    },
+                    Anchor::Bottom => Point {
+                        x: centre_x,
+                        y: bottom,
+                    },
+                    Anchor::BottomLeft => Point {
+                        x: containing.x,
+                        y: bottom,
+                    },
+                    Anchor::Left => Point {
+                        x: containing.x,
+                        y: centre_y,
+                    },
+                    Anchor::TopLeft => Point {
+                        x: containing.x,
+                        y: containing.y,
+                    },
+                };
+                let (offset_x, offset_y) = offset.resolve(
+                    containing.width,
+                    containing.height,
+                    &child.data.computed_layout.units,
+                );
+                let x = anchor_point.x + offset_x;
+                Point {
+                    // the left anchors and offset are towards the start, which is on the right
+                    x: match child.data.computed_layout.direction.is_rtl() {
+                        true => containing.mirror_x(x, width),
+                        false => x,
+                    },
+                    y: anchor_point.y + offset_y,
+                }
+            }
+            _ => unreachable!("only absolutely positioned children are anchored"),
+        }
+    }
+
+    /// Resolves the width and height of the children of the given node.
+    /// This process is contigent on the width/height and padding of the given node.
+    ///
+    /// Cross axis sizes are independent of siblings, so are resolved first. Along the main axis, the
+    /// fixed size children are resolved before the children are broken into lines, if wrapping, and
+    /// then the space left over in each line is shared between its weighted children. When a weighted
+    /// child is clamped by its min/max size, it is frozen at that size and the space it freed or took
+    /// is shared again between the remaining weighted children
+    fn resolve_child_dimensions(comp_tree: &mut Node<Component>) {
+        if comp_tree.has_no_children() {
+            comp_tree.data.computed_layout.lines.clear();
+            return;
+        }
+
+        if comp_tree.data.effective_layout.orientation.is_grid() {
+            return grid::resolve_child_dimensions(comp_tree);
+        }
+
+        if comp_tree.data.effective_layout.orientation.is_constraints() {
+            return constraints::resolve_child_dimensions(comp_tree);
+        }
+
+        if comp_tree.data.effective_layout.orientation.is_stack() {
+            return Self::resolve_stack_dimensions(comp_tree);
+        }
+
+        let parent_padding = &comp_tree.data.computed_layout.padding;
+        let parent_width = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+        let parent_height = comp_tree.data.computed_layout.height - parent_padding.vertical();
+        let parent_align = &comp_tree.data.effective_layout.align;
+        let parent_gap = comp_tree.data.effective_layout.gap;
+        let is_row = comp_tree.data.effective_layout.orientation.is_row();
+        let is_wrap = match &comp_tree.data.effective_layout.wrap {
+            Wrap::NoWrap => false,
+            Wrap::Wrap => true,
+        };
+        let (parent_main, parent_cross) = if is_row {
+            (parent_width, parent_height)
+        } else {
+            (parent_height, parent_width)
+        };
+
+        // cross axis
+        for child in &mut comp_tree.children {
+            let layout = &child.data.effective_layout;
+            let computed = &mut child.data.computed_layout;
+            let align = layout.align_self.as_ref().unwrap_or(parent_align);
+            // stretched children fill the cross axis whatever size they asked for
+            let stretch = matches!(align, Align::Stretch) && !layout.position.is_absolute();
+            // when wrapping, filling children fill their line rather than the parent. The size of
+            // the line isn't known yet, so for now they only take the size of their content
+            let fills_line = is_wrap && Self::fills_cross(layout, is_row, stretch);
+
+            if is_row {
+                computed.height = layout.clamp_height(match fills_line {
+                    true => computed.intrinsic_height,
+                    false => Self::resolve_cross_size(
+                        &layout.height,
+                        parent_height,
+                        layout.margin.vertical(),
+                        computed.intrinsic_height,
+                        stretch,
+                        &computed.units,
+                    ),
+                });
+            } else {
+                computed.width = layout.clamp_width(match fills_line {
+                    true => computed.intrinsic_width,
+                    false => Self::resolve_cross_size(
+                        &layout.width,
+                        parent_width,
+                        layout.margin.horizontal(),
+                        computed.intrinsic_width,
+                        stretch,
+                        &computed.units,
+                    ),
+                });
+            }
+        }
+
+        // main axis, fixed size children
+        let mut weighted = Vec::new();
+        let mut is_weighted = vec![false; comp_tree.children.len()];
+        // the size each flowed child would take before any space is shared, used to break lines
+        let mut basis = Vec::with_capacity(comp_tree.children.len());
+        for (i, child) in comp_tree.children.iter_mut().enumerate() {
+            let layout = &child.data.effective_layout;
+            let computed = &mut child.data.computed_layout;
+            let is_absolute = layout.position.is_absolute();
+            let (size, intrinsic, margin) = if is_row {
+                (
+                    &layout.width,
+                    computed.intrinsic_width,
+                    layout.margin.horizontal(),
+                )
+            } else {
+                (
+                    &layout.height,
+                    computed.intrinsic_height,
+                    layout.margin.vertical(),
+                )
+            };
+
+            let fixed = match (size, layout.aspect_ratio) {
+                // the cross axis is already known, so a column can derive its height from it
+                (_, Some(ratio)) if !is_row => Some(computed.width / ratio),
+                (Size::Constant(size), _) => Some(*size),
+                (Size::Percent(percent), _) => Some(parent_main * percent / 100.0),
+                (Size::Length(length), _) => Some(length.resolve(parent_main, &computed.units)),
+                (Size::FitContent, _) => Some(intrinsic),
+                // absolutely positioned children have no siblings to share with
+                (Size::Fill | Size::Weighted(_), _) if is_absolute => Some(parent_main - margin),
+                (Size::Fill, _) => None,
+                (Size::Weighted(_), _) => None,
+            };
+
+            let size = match fixed {
+                Some(size) => {
+                    if is_row {
+                        computed.width = layout.clamp_width(size);
+                        computed.width
+                    } else {
+                        computed.height = layout.clamp_height(size);
+                        computed.height
+                    }
+                }
+                None => {
+                    let weight = match size {
+                        Size::Weighted(weight) => *weight,
+                        _ => 1.0,
+                    };
+                    weighted.push((i, weight, false));
+                    is_weighted[i] = true;
+
+                    if is_row {
+                        layout.clamp_width(intrinsic)
+                    } else {
+                        layout.clamp_height(intrinsic)
+                    }
+                }
+            };
+
+            basis.push(match is_absolute {
+                true => None,
+                false => Some(margin + size),
+            });
+        }
+
+        // break flowed children into lines, where a child that would overflow the current line
+        // starts a new one, unless it is the first in its line
+        let mut lines = Vec::new();
+        let mut line = FlowLine::default();
+        let mut line_main = 0.0;
+        let mut line_count = 0;
+        for (i, basis) in basis.iter().enumerate() {
+            let Some(basis) = basis else {
+                continue;
+            };
+
+            if is_wrap && line_count > 0 && line_main + parent_gap + basis > parent_main {
+                line.end = i;
+                lines.push(line);
+                line = FlowLine {
+                    start: i,
+                    ..Default::default()
+                };
+                line_main = 0.0;
+                line_count = 0;
+            }
+
+            if line_count > 0 {
+                line_main += parent_gap;
+            }
+            line_main += basis;
+            line_count += 1;
+        }
+        line.end = comp_tree.children.len();
+        lines.push(line);
+
+        // main axis, weighted children
+        for line in &lines {
+            let mut flow_count = 0.0;
+            let mut total_fixed = 0.0;
+            for (i, child) in comp_tree.children[line.start..line.end].iter().enumerate() {
+                if child.data.effective_layout.position.is_absolute() {
+                    continue;
+                }
+
+                let margin = &child.data.effective_layout.margin;
+                flow_count += 1.0;
+                total_fixed += if is_row {
+                    margin.horizontal()
+                } else {
+                    margin.vertical()
+                };
+                if !is_weighted[line.start + i] {
+                    total_fixed += child.data.computed_layout.main_size(is_row);
+                }
+            }
+            // the gap only sits between flowed siblings, so there is one fewer than there are
+            // children
+            total_fixed += parent_gap * f32::max(flow_count - 1.0, 0.0);
+
+            // weighted children are in order, so those in this line are contiguous
+            let start = weighted.partition_point(|(i, _, _)| *i < line.start);
+            let end = weighted.partition_point(|(i, _, _)| *i < line.end);
+            Self::distribute_weighted(
+                &mut comp_tree.children,
+                &mut weighted[start..end],
+                parent_main - total_fixed,
+                is_row,
+            );
+        }
+
+        // a row only knows the width of its children now, so can derive their heights
+        if is_row {
+            for child in &mut comp_tree.children {
+                if let Some(ratio) = child.data.effective_layout.aspect_ratio {
+                    child.data.computed_layout.height = child
+                        .data
+                        .effective_layout
+                        .clamp_height(child.data.computed_layout.width / ratio);
+                }
+            }
+        }
+
+        // a line is as large as its largest child along the cross axis, which its filling children
+        // then fill. Without wrapping, the only line spans the whole parent
+        for line in &mut lines {
+            if !is_wrap {
+                line.cross_size = parent_cross;
+                continue;
+            }
+
+            let children = &mut comp_tree.children[line.start..line.end];
+            for child in children.iter() {
+                if child.data.effective_layout.position.is_absolute() {
+                    continue;
+                }
+
+                let margin = &child.data.effective_layout.margin;
+                line.cross_size = line.cross_size.max(if is_row {
+                    margin.vertical() + child.data.computed_layout.height
+                } else {
+                    margin.horizontal() + child.data.computed_layout.width
+                });
+            }
+
+            for child in children.iter_mut() {
+                let layout = &child.data.effective_layout;
+                let align = layout.align_self.as_ref().unwrap_or(parent_align);
+                let stretch = matches!(align, Align::Stretch) && !layout.position.is_absolute();
+                if !Self::fills_cross(layout, is_row, stretch)
+                    || (is_row && layout.aspect_ratio.is_some())
+                {
+                    continue;
+                }
+
+                if is_row {
+                    child.data.computed_layout.height =
+                        layout.clamp_height(line.cross_size - layout.margin.vertical());
+                } else {
+                    child.data.computed_layout.width =
+                        layout.clamp_width(line.cross_size - layout.margin.horizontal());
+                }
+            }
+        }
+
+        comp_tree.data.computed_layout.lines = lines;
+    }
+
+    /// Shares the free space along the main axis between the given weighted children, by weight.
+    /// Each is given as its index in the children, its weight, and whether it has been frozen
+    fn distribute_weighted(
+        children: &mut [Node<Component>],
+        weighted: &mut [(usize, f32, bool)],
+        free: f32,
+        is_row: bool,
+    ) {
+        loop {
+            let mut total_frozen = 0.0;
+            let mut total_weight = 0.0;
+            for (i, weight, frozen) in weighted.iter() {
+                if *frozen {
+                    total_frozen += children[*i].data.computed_layout.main_size(is_row);
+                } else {
+                    total_weight += weight;
+                }
+            }
+
+            if total_weight <= 0.0 {
+                break;
+            }
+
+            // if the fixed size children already overflow, there is nothing left to share
+            let free = f32::max(free - total_frozen, 0.0);
+            let mut total_violation = 0.0;
+            for (i, weight, frozen) in weighted.iter() {
+                if *frozen {
+                    continue;
+                }
+
+                let child = &mut children[*i].data;
+                let target = free * weight / total_weight;
+                let clamped = if is_row {
+                    child.computed_layout.width = child.effective_layout.clamp_width(target);
+                    child.computed_layout.width
+                } else {
+                    child.computed_layout.height = child.effective_layout.clamp_height(target);
+                    child.computed_layout.height
+                };
+                total_violation += clamped - target;
+            }
+
+            if total_violation == 0.0 {
+                break;
+            }
+
+            // freeze those that were clamped in the direction of the overall violation, since they
+            // would remain clamped however the rest of the space is shared
+            for (i, weight, frozen) in weighted.iter_mut() {
+                if *frozen {
+                    continue;
+                }
+
+                let target = free * *weight / total_weight;
+                let clamped = children[*i].data.computed_layout.main_size(is_row);
+                *frozen = (total_violation > 0.0 && clamped > target)
+                    || (total_violation < 0.0 && clamped < target);
+            }
+        }
+    }
+
+    /// Resolves the width and height of the children of the given stack node, where each child is
+    /// sized against the whole content box of the parent, as if it were the cross axis of a `Row` or
+    /// `Column`
+    fn resolve_stack_dimensions(comp_tree: &mut Node<Component>) {
+        let parent_padding = &comp_tree.data.computed_layout.padding;
+        let parent_width = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+        let parent_height = comp_tree.data.computed_layout.height - parent_padding.vertical();
+        let parent_align = &comp_tree.data.effective_layout.align;
+
+        for child in &mut comp_tree.children {
+            let layout = &child.data.effective_layout;
+            let computed = &mut child.data.computed_layout;
+            let align = layout.align_self.as_ref().unwrap_or(parent_align);
+            let stretch = matches!(align, Align::Stretch) && !layout.position.is_absolute();
+
+            computed.width = layout.clamp_width(Self::resolve_cross_size(
+                &layout.width,
+                parent_width,
+                layout.margin.horizontal(),
+                computed.intrinsic_width,
+                stretch,
+                &computed.units,
+            ));
+            computed.height = layout.clamp_height(match layout.aspect_ratio {
+                Some(ratio) => computed.width / ratio,
+                None => Self::resolve_cross_size(
+                    &layout.height,
+                    parent_height,
+                    layout.margin.vertical(),
+                    computed.intrinsic_height,
+                    stretch,
+                    &computed.units,
+                ),
+            });
+        }
+    }
+
+    /// Resolves the screen position of the children of the given stack node, where each child is
+    /// aligned within the content box of the parent on both axes
+    fn resolve_stack_positions(comp_tree: &mut Node<Component>) {
+        let parent_padding = &comp_tree.data.computed_layout.padding;
+        let parent_pos = &Point {
+            x: comp_tree.data.computed_layout.position.x + parent_padding.left,
+            y: comp_tree.data.computed_layout.position.y + parent_padding.top,
+        };
+        let parent_w = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+        let parent_h = comp_tree.data.computed_layout.height - parent_padding.vertical();
+        let parent_align = &comp_tree.data.effective_layout.align;
+
+        for child in &mut comp_tree.children {
+            let (offset_x, offset_y) = match &child.data.effective_layout.position {
+                Position::Static | Position::Sticky(_) => (0.0, 0.0),
+                Position::Relative(offset) => {
+                    offset.resolve(parent_w, parent_h, &child.data.computed_layout.units)
+                }
+                Position::Absolute(_, _) => continue,
+            };
+
+            let margin = &child.data.effective_layout.margin;
+            let align = child
+                .data
+                .effective_layout
+                .align_self
+                .as_ref()
+                .unwrap_or(parent_align);
+            let computed = &child.data.computed_layout;
+
+            child.data.computed_layout.position = Point {
+                x: parent_pos.x
+                    + offset_x
+                    + align.offset(parent_w, computed.width, margin.left, margin.right),
+                y: parent_pos.y
+                    + offset_y
+                    + align.offset(parent_h, computed.height, margin.top, margin.bottom),
+            };
+        }
+    }
+
+    /// Whether the given child fills the cross axis of its parent, or its line when wrapping
+    fn fills_cross(layout: &Layout, is_row: bool, stretch: bool) -> bool {
+        let size = if is_row {
+            &layout.height
+        } else {
+            &layout.width
+        };
+        stretch || matches!(size, Size::Fill | Size::Weighted(_))
+    }
+
+    /// Resolves the size of a child along the cross axis of its parent, where `available` is the size
+    /// of the parent content box along that axis, and `units` those of the child
+    pub(crate) fn resolve_cross_size(
+        size: &Size,
+        available: f32,
+        margin: f32,
+        intrinsic: f32,
+        stretch: bool,
+        units: &Units,
+    ) -> f32 {
+        match size {
+            _ if stretch => available - margin,
+            Size::Constant(size) => *size,
+            Size::Percent(percent) => available * percent / 100.0,
+            Size::Length(length) => length.resolve(available, units),
+            Size::Fill | Size::Weighted(_) => available - margin,
+            Size::FitContent => intrinsic,
+        }
+    }
+}
+
+/// What has to be recomputed for a component since it was last laid out and painted
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Dirty {
+    /// The style of the component has to be cascaded again, such as after its overrides changed
+    pub(crate) style: bool,
+    pub(crate) layout: bool,
+    pub(crate) paint: bool,
+    pub(crate) descendant_style: bool,
+    /// Something below the component needs to be laid out, which may in turn change its own layout
+    pub(crate) descendant_layout: bool,
+    pub(crate) descendant_paint: bool,
+}
+
+impl Dirty {
+    /// A new component has never been laid out or painted
+    const fn new() -> Self {
+        Self {
+            style: true,
+            layout: true,
+            paint: true,
+            descendant_style: false,
+            descendant_layout: false,
+            descendant_paint: false,
+        }
+    }
+
+    /// Marks whatever the given child of the component needs as needed by a descendant
+    fn include(&mut self, child: Dirty) {
+        self.descendant_style |= child.needs_style();
+        self.descendant_layout |= child.needs_layout();
+        self.descendant_paint |= child.needs_paint();
+    }
+
+    pub(crate) fn needs_style(&self) -> bool {
+        self.style || self.descendant_style
+    }
+
+    pub(crate) fn needs_layout(&self) -> bool {
+        self.layout || self.descendant_layout
+    }
+
+    pub(crate) fn needs_paint(&self) -> bool {
+        self.paint || self.descendant_paint
+    }
+}
+
+/// The states a component can be put in by interacting with it
+#[derive(Clone, Copy)]
+enum State {
+    Hovered,
+    Pressed,
+    Focused,
+}
+
+/// The states a component is in, which apply the overrides it has for them
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Interaction {
+    pub(crate) hovered: bool,
+    pub(crate) pressed: bool,
+    pub(crate) focused: bool,
+}
+
+impl Interaction {
+    fn get(&self, state: State) -> bool {
+        match state {
+            State::Hovered => self.hovered,
+            State::Pressed => self.pressed,
+            State::Focused => self.focused,
+        }
+    }
+
+    fn get_mut(&mut self, state: State) -> &mut bool {
+        match state {
+            State::Hovered => &mut self.hovered,
+            State::Pressed => &mut self.pressed,
+            State::Focused => &mut self.focused,
+        }
+    }
+}
+
+/// Measures the content of a leaf component, such as text or an image, for `Size::FitContent`
+pub type Measure = Box<dyn Fn(&Component) -> Dimensions>;
+
+pub struct Component {
+    /// Identifies the component, such as in `Components::dump` and diagnostics
+    pub id: Option<String>,
+    /// The type of the component, such as `button`, which is matched by type selectors in `Rules`
+    pub tag: Option<String>,
+    /// The classes of the component, which are matched by class selectors in `Rules`. As with the
+    /// style, the component must be marked to be restyled once they change
+    pub classes: Vec<String>,
+    /// Overrides of the style sheet for this component, which are cascaded into its computed style
+    /// during layout. Changes are only cascaded once the component is marked to be restyled, which
+    /// `set_style` does
+    pub style: Style,
+    /// Overrides of the style for the states the component can be in, over its other overrides
+    pub state_styles: StateStyles,
+    pub computed_style: ComputedStyle,
+    pub layout: Layout,
+    /// the layout the component is laid out by, which is its own layout with the layout properties of
+    /// the rules it matches in place of any it leaves at their initial values
+    pub(crate) effective_layout: Layout,
+    pub computed_layout: ComputedLayout,
+    pub measure: Option<Measure>,
+    /// Whether the component can take focus, by pressing it or moving focus to it with `Tab`
+    pub focusable: bool,
+    /// Whether the component is disabled, in which case it can't take focus, and only its overrides for
+    /// being disabled apply. As with the style, the component must be marked to be restyled once this
+    /// changes
+    pub disabled: bool,
+    pub(crate) interaction: Interaction,
+    pub(crate) dirty: Dirty,
+    /// the rules that matched during the last cascade, if it has been cascaded since the rules were
+    /// set, which are only matched again once its tag, classes or id change
+    pub(crate) matched_rules: Option<MatchedRules>,
+    // pub connections: Vec<Box<dyn Msg>>
+}
+
+impl Component {
+    pub fn new() -> Self {
+        Self {
+            id: None,
+            tag: None,
+            classes: Vec::new(),
+            style: Style::default(),
+            state_styles: StateStyles::default(),
+            computed_style: ComputedStyle::cascade(&Style::default(), None, &LIGHT),
+            layout: Layout::default(),
+            effective_layout: Layout::default(),
+            computed_layout: ComputedLayout::default(),
+            measure: None,
+            focusable: false,
+            disabled: false,
+            interaction: Interaction::default(),
+            dirty: Dirty::new(),
+            matched_rules: None,
+        }
+    }
+
+    /// Returns how the component is named in dumps and diagnostics, which is its id, or otherwise its
+    /// index amongst its siblings
+    pub(crate) fn label(&self, index: usize) -> String {
+        match &self.id {
+            Some(id) => format!("#{id}"),
+            None => format!("[{index}]"),
+        }
+    }
+
+    /// Whether the pointer is over the component, or any of its descendants
+    pub fn is_hovered(&self) -> bool {
+        self.interaction.hovered
+    }
+
+    /// Whether the component, or any of its descendants, is being pressed
+    pub fn is_pressed(&self) -> bool {
+        self.interaction.pressed
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.interaction.focused
+    }
+
+    fn can_focus(&self) -> bool {
+        self.focusable && !self.disabled
+    }
+
+    /// Returns the overrides for the states the component is in, where those for being disabled replace
+    /// the rest, and those for being pressed take precedence over being focused, then hovered
+    fn state_style(&self) -> Style {
+        if self.disabled {
+            return self.state_styles.disabled.clone();
+        }
+
+        let mut style = Style::default();
+        for (active, overrides) in [
+            (self.interaction.hovered, &self.state_styles.hovered),
+            (self.interaction.focused, &self.state_styles.focused),
+            (self.interaction.pressed, &self.state_styles.pressed),
+        ] {
+            if active {
+                style = overrides.or(&style);
+            }
+        }
+        style
+    }
+
+    /// Replaces the style overrides of the component, which are cascaded with the next layout
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+        self.restyle();
+    }
+
+    /// Marks the component to be restyled, for changes to its style or state overrides, tag, classes,
+    /// id, or whether it is disabled
+    pub fn restyle(&mut self) {
+        self.dirty.style = true;
+    }
+
+    /// Marks the component to be painted again, for changes that don't affect its layout
+    pub fn repaint(&mut self) {
+        self.dirty.paint = true;
+    }
+
+    /// Marks the component to be laid out again, along with everything that depends on its size
+    pub fn relayout(&mut self) {
+        self.dirty.layout = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::{
+        Anchor, Attribute, Constraint, Edge, GridPlacement, GridTemplate, Justify, Margin, Offset,
+        Orientation, Padding, StickyOffset, Strength, Track,
+    };
+    use crate::rules::Rules;
+    use crate::style::Colour;
+
+    const SCREEN: Dimensions = Dimensions {
+        width: 800.0,
+        height: 600.0,
+    };
+
+    fn component(width: Size, height: Size) -> Node<Component> {
+        let mut node = Node::new(Component::new());
+        node.data.layout.width = width;
+        node.data.layout.height = height;
+        node
+    }
+
+    fn compute(root: Node<Component>) -> Node<Component> {
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.layers.pop().unwrap().component_tree
+    }
+
+    fn rect(node: &Node<Component>) -> (f32, f32, f32, f32) {
+        let layout = &node.data.computed_layout;
+        (
+            layout.position.x,
+            layout.position.y,
+            layout.width,
+            layout.height,
+        )
+    }
+
+    #[test]
+    fn padded_row() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Row;
+        root.data.layout.padding = Padding::new(10.0, 20.0, 30.0, 40.0);
+        root.push_child(component(Size::Constant(100.0), Size::Fill));
+        root.push_child(component(Size::Fill, Size::Constant(50.0)));
+        root.push_child(component(Size::Fill, Size::Fill));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root), (0.0, 0.0, 800.0, 600.0));
+        assert_eq!(rect(&root.children[0]), (40.0, 10.0, 100.0, 560.0));
+        assert_eq!(rect(&root.children[1]), (140.0, 10.0, 320.0, 50.0));
+        assert_eq!(rect(&root.children[2]), (460.0, 10.0, 320.0, 560.0));
+    }
+
+    #[test]
+    fn padded_column() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.padding = Padding::uniform(25.0);
+        root.push_child(component(Size::Fill, Size::Constant(150.0)));
+        root.push_child(component(Size::Constant(100.0), Size::Fill));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (25.0, 25.0, 750.0, 150.0));
+        assert_eq!(rect(&root.children[1]), (25.0, 175.0, 100.0, 400.0));
+    }
+
+    #[test]
+    fn nested_padding() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.padding = Padding::uniform(10.0);
+
+        let mut inner = component(Size::Constant(200.0), Size::Constant(100.0));
+        inner.data.layout.orientation = Orientation::Column;
+        inner.data.layout.padding = Padding::new(5.0, 0.0, 15.0, 20.0);
+        inner.push_child(component(Size::Fill, Size::Fill));
+        root.push_child(inner);
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (10.0, 10.0, 200.0, 100.0));
+        assert_eq!(
+            rect(&root.children[0].children[0]),
+            (30.0, 15.0, 180.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn padded_absolute_anchor() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.padding = Padding::uniform(20.0);
+
+        let mut bottom_right = component(Size::Constant(50.0), Size::Constant(40.0));
+        bottom_right.data.layout.position =
+            Position::Absolute(Offset::none(), crate::layout::Anchor::BottomRight);
+        root.push_child(bottom_right);
+
+        let mut relative = component(Size::Constant(50.0), Size::Constant(40.0));
+        relative.data.layout.position = Position::Relative(Offset::new(5.0, 5.0));
+        root.push_child(relative);
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (730.0, 540.0, 50.0, 40.0));
+        assert_eq!(rect(&root.children[1]), (25.0, 25.0, 50.0, 40.0));
+    }
+
+    #[test]
+    fn absolute_in_positioned_ancestor() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.push_child(component(Size::Constant(100.0), Size::Fill));
+
+        let mut panel = component(Size::Constant(400.0), Size::Constant(300.0));
+        panel.data.layout.position = Position::Relative(Offset::none());
+        let mut wrapper = component(Size::Constant(200.0), Size::Constant(100.0));
+        wrapper.data.layout.padding = Padding::uniform(10.0);
+
+        // placed within the panel, as the wrapper between them is not positioned
+        let mut modal = component(Size::Percent(50.0), Size::Constant(50.0));
+        modal.data.layout.position = Position::Absolute(Offset::none(), Anchor::Centre);
+        wrapper.push_child(modal);
+
+        let mut inner = component(Size::Constant(200.0), Size::Constant(100.0));
+        inner.data.layout.position = Position::Absolute(Offset::new(0.0, 0.0), Anchor::TopLeft);
+        let mut toast = component(Size::Constant(200.0), Size::Constant(40.0));
+        toast.data.layout.position =
+            Position::Absolute(Offset::percent(0.0, -10.0), Anchor::Bottom);
+        inner.push_child(toast);
+        wrapper.push_child(inner);
+
+        panel.push_child(wrapper);
+        root.push_child(panel);
+
+        // the root is positioned in the screen like any other containing block
+        let mut overlay = component(Size::Percent(50.0), Size::Percent(50.0));
+        overlay.data.layout.position = Position::Absolute(Offset::none(), Anchor::Centre);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.layers.push(Layer {
+            component_tree: overlay,
+        });
+        components.compute_layout(SCREEN);
+
+        let wrapper = &components.layers[0].component_tree.children[1].children[0];
+        assert_eq!(rect(&wrapper.children[0]), (200.0, 125.0, 200.0, 50.0));
+        // the toast is within the absolutely positioned `inner`, so is placed in that instead
+        assert_eq!(rect(&wrapper.children[1]), (100.0, 0.0, 200.0, 100.0));
+        assert_eq!(
+            rect(&wrapper.children[1].children[0]),
+            (100.0, 50.0, 200.0, 40.0)
+        );
+        assert_eq!(
+            rect(&components.layers[1].component_tree),
+            (200.0, 150.0, 400.0, 300.0)
+        );
+
+        // resizing the panel leaves the wrapper where it was, but moves what is placed in the panel
+        let panel = &mut components.layers[0].component_tree.children[1];
+        panel.data.layout.width = Size::Constant(600.0);
+        panel.data.relayout();
+        components.compute_layout(SCREEN);
+
+        let wrapper = &components.layers[0].component_tree.children[1].children[0];
+        assert_eq!(rect(wrapper), (100.0, 0.0, 200.0, 100.0));
+        assert_eq!(rect(&wrapper.children[0]), (250.0, 125.0, 300.0, 50.0));
+    }
+
+    #[test]
+    fn absolute_in_scroll_container() {
+        let mut list = scroll_list();
+        list.data.layout.position = Position::Relative(Offset::none());
+        let mut badge = component(Size::Constant(20.0), Size::Constant(20.0));
+        badge.data.layout.position = Position::Absolute(Offset::none(), Anchor::TopRight);
+        list.push_child(badge);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: list,
+        });
+        components.compute_layout(SCREEN);
+        components.scroll(50.0, 50.0, 0.0, -100.0);
+        components.compute_layout(SCREEN);
+
+        // the badge moves with the content of its containing block, but doesn't add to it
+        let list = &components.layers[0].component_tree;
+        assert_eq!(list.data.computed_layout.content_height, 1000.0);
+        assert_eq!(rect(&list.children[10]), (170.0, -90.0, 20.0, 20.0));
+    }
+
+    #[test]
+    fn right_to_left_row() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.direction = Some(Direction::RightToLeft);
+        root.data.layout.padding = Padding::new(0.0, 0.0, 0.0, 20.0);
+        root.data.layout.gap = 5.0;
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+
+        let mut inherits = component(Size::Constant(50.0), Size::Constant(50.0));
+        inherits.data.layout.margin = Margin::new(0.0, 0.0, 0.0, 10.0);
+        inherits.push_child(component(Size::Constant(20.0), Size::Constant(20.0)));
+        root.push_child(inherits);
+
+        let mut overrides = component(Size::Constant(50.0), Size::Constant(50.0));
+        overrides.data.layout.direction = Some(Direction::LeftToRight);
+        overrides.push_child(component(Size::Constant(20.0), Size::Constant(20.0)));
+        root.push_child(overrides);
+
+        let mut start = component(Size::Constant(30.0), Size::Constant(30.0));
+        start.data.layout.position = Position::Absolute(Offset::new(10.0, 0.0), Anchor::Left);
+        root.push_child(start);
+
+        let root = compute(root);
+
+        // the left padding and margin are at the start, on the right
+        assert_eq!(rect(&root.children[0]), (680.0, 0.0, 100.0, 100.0));
+        assert_eq!(rect(&root.children[1]), (615.0, 0.0, 50.0, 50.0));
+        assert_eq!(
+            rect(&root.children[1].children[0]),
+            (645.0, 0.0, 20.0, 20.0)
+        );
+        assert_eq!(rect(&root.children[2]), (560.0, 0.0, 50.0, 50.0));
+        assert_eq!(
+            rect(&root.children[2].children[0]),
+            (560.0, 0.0, 20.0, 20.0)
+        );
+        assert_eq!(rect(&root.children[3]), (740.0, 285.0, 30.0, 30.0));
+    }
+
+    #[test]
+    fn row_margins_and_gap() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Row;
+        root.data.layout.gap = 10.0;
+
+        let mut first = component(Size::Constant(100.0), Size::Fill);
+        first.data.layout.margin = Margin::new(5.0, 15.0, 5.0, 15.0);
+        root.push_child(first);
+        root.push_child(component(Size::Fill, Size::Constant(50.0)));
+        let mut last = component(Size::Fill, Size::Fill);
+        last.data.layout.margin = Margin::uniform(20.0);
+        root.push_child(last);
+
+        let root = compute(root);
+
+        // 800 - (100 + 30 + 40) - 2 * 10 = 610 shared between two fill children
+        assert_eq!(rect(&root.children[0]), (15.0, 5.0, 100.0, 590.0));
+        assert_eq!(rect(&root.children[1]), (140.0, 0.0, 305.0, 50.0));
+        assert_eq!(rect(&root.children[2]), (475.0, 20.0, 305.0, 560.0));
+    }
+
+    #[test]
+    fn column_margins_and_gap() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.padding = Padding::uniform(10.0);
+        root.data.layout.gap = 20.0;
+
+        let mut first = component(Size::Fill, Size::Constant(100.0));
+        first.data.layout.margin = Margin::new(0.0, 30.0, 10.0, 0.0);
+        root.push_child(first);
+        root.push_child(component(Size::Fill, Size::Fill));
+
+        // absolute children take no part in the flow, so add no gap
+        let mut absolute = component(Size::Constant(10.0), Size::Constant(10.0));
+        absolute.data.layout.position =
+            Position::Absolute(Offset::none(), crate::layout::Anchor::TopLeft);
+        root.push_child(absolute);
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (10.0, 10.0, 750.0, 100.0));
+        assert_eq!(rect(&root.children[1]), (10.0, 140.0, 780.0, 450.0));
+    }
+
+    fn justified_row(justify: Justify) -> Vec<(f32, f32, f32, f32)> {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.justify = justify;
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+        root.push_child(component(Size::Constant(200.0), Size::Constant(100.0)));
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+
+        compute(root).children.iter().map(rect).collect()
+    }
+
+    #[test]
+    fn justify_row() {
+        let xs = |justify| -> Vec<f32> {
+            justified_row(justify)
+                .into_iter()
+                .map(|(x, _, _, _)| x)
+                .collect()
+        };
+
+        // 400 of free space in each case
+        assert_eq!(xs(Justify::Start), vec![0.0, 100.0, 300.0]);
+        assert_eq!(xs(Justify::Centre), vec![200.0, 300.0, 500.0]);
+        assert_eq!(xs(Justify::End), vec![400.0, 500.0, 700.0]);
+        assert_eq!(xs(Justify::SpaceBetween), vec![0.0, 300.0, 700.0]);
+        assert_eq!(
+            xs(Justify::SpaceAround),
+            vec![200.0 / 3.0, 100.0 + 200.0, 700.0 - 200.0 / 3.0]
+        );
+        assert_eq!(xs(Justify::SpaceEvenly), vec![100.0, 300.0, 600.0]);
+    }
+
+    #[test]
+    fn justify_overflow_falls_back() {
+        let mut root = component(Size::Constant(100.0), Size::Constant(100.0));
+        root.data.layout.justify = Justify::SpaceEvenly;
+        root.push_child(component(Size::Constant(80.0), Size::Constant(10.0)));
+        root.push_child(component(Size::Constant(80.0), Size::Constant(10.0)));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]).0, -30.0);
+        assert_eq!(rect(&root.children[1]).0, 50.0);
+    }
+
+    #[test]
+    fn align_row_with_override() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.align = Align::Centre;
+        root.data.layout.gap = 10.0;
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+
+        let mut end = component(Size::Constant(100.0), Size::Constant(100.0));
+        end.data.layout.align_self = Some(Align::End);
+        end.data.layout.margin = Margin::uniform(20.0);
+        root.push_child(end);
+
+        let mut stretch = component(Size::Constant(100.0), Size::Constant(100.0));
+        stretch.data.layout.align_self = Some(Align::Stretch);
+        stretch.data.layout.margin = Margin::new(5.0, 0.0, 15.0, 0.0);
+        root.push_child(stretch);
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (0.0, 250.0, 100.0, 100.0));
+        assert_eq!(rect(&root.children[1]), (130.0, 480.0, 100.0, 100.0));
+        assert_eq!(rect(&root.children[2]), (260.0, 5.0, 100.0, 580.0));
+    }
+
+    #[test]
+    fn centred_button_bar() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.justify = Justify::End;
+        root.data.layout.align = Align::Centre;
+        root.data.layout.padding = Padding::uniform(10.0);
+
+        let mut bar = component(Size::Constant(300.0), Size::Constant(40.0));
+        bar.data.layout.justify = Justify::SpaceBetween;
+        bar.push_child(component(Size::Constant(80.0), Size::Fill));
+        bar.push_child(component(Size::Constant(80.0), Size::Fill));
+        root.push_child(bar);
+
+        let root = compute(root);
+        let bar = &root.children[0];
+
+        assert_eq!(rect(bar), (250.0, 550.0, 300.0, 40.0));
+        assert_eq!(rect(&bar.children[0]), (250.0, 550.0, 80.0, 40.0));
+        assert_eq!(rect(&bar.children[1]), (470.0, 550.0, 80.0, 40.0));
+    }
+
+    #[test]
+    fn fit_content_row() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Column;
+
+        let mut row = component(Size::FitContent, Size::FitContent);
+        row.data.layout.padding = Padding::uniform(10.0);
+        row.data.layout.gap = 5.0;
+        row.push_child(component(Size::Constant(100.0), Size::Constant(40.0)));
+        let mut tall = component(Size::Constant(50.0), Size::Constant(60.0));
+        tall.data.layout.margin = Margin::new(0.0, 0.0, 0.0, 20.0);
+        row.push_child(tall);
+        root.push_child(row);
+        root.push_child(component(Size::Fill, Size::Fill));
+
+        let root = compute(root);
+        let row = &root.children[0];
+
+        assert_eq!(rect(row), (0.0, 0.0, 195.0, 80.0));
+        assert_eq!(rect(&row.children[0]), (10.0, 10.0, 100.0, 40.0));
+        assert_eq!(rect(&row.children[1]), (135.0, 10.0, 50.0, 60.0));
+        assert_eq!(rect(&root.children[1]), (0.0, 80.0, 800.0, 520.0));
+    }
+
+    #[test]
+    fn fit_content_nested_measure() {
+        let mut root = component(Size::FitContent, Size::FitContent);
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.padding = Padding::uniform(4.0);
+
+        let mut label = component(Size::FitContent, Size::FitContent);
+        label.data.style.font_size = Some(20.0);
+        label.data.measure = Some(Box::new(|component: &Component| Dimensions {
+            width: 5.0 * component.computed_style.font_size,
+            height: component.computed_style.font_size,
+        }));
+        label.data.layout.padding = Padding::new(0.0, 8.0, 0.0, 8.0);
+        root.push_child(label);
+
+        // a filling child contributes its own content, and then takes what the column has left
+        let mut filler = component(Size::Fill, Size::FitContent);
+        filler.push_child(component(Size::Constant(30.0), Size::Constant(30.0)));
+        root.push_child(filler);
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root), (0.0, 0.0, 124.0, 58.0));
+        assert_eq!(rect(&root.children[0]), (4.0, 4.0, 116.0, 20.0));
+        assert_eq!(rect(&root.children[1]), (4.0, 24.0, 116.0, 30.0));
+    }
+
+    #[test]
+    fn weighted_three_panes() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.gap = 20.0;
+        root.push_child(component(Size::Weighted(1.0), Size::Fill));
+        root.push_child(component(Size::Weighted(2.0), Size::Weighted(3.0)));
+        root.push_child(component(Size::Fill, Size::Percent(50.0)));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 190.0, 600.0));
+        assert_eq!(rect(&root.children[1]), (210.0, 0.0, 380.0, 600.0));
+        assert_eq!(rect(&root.children[2]), (610.0, 0.0, 190.0, 300.0));
+    }
+
+    #[test]
+    fn percent_of_content_box() {
+        let mut root = component(Size::Percent(50.0), Size::Percent(100.0));
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.padding = Padding::uniform(50.0);
+        root.push_child(component(Size::Percent(25.0), Size::Percent(10.0)));
+        root.push_child(component(Size::Weighted(2.0), Size::Fill));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root), (0.0, 0.0, 400.0, 600.0));
+        assert_eq!(rect(&root.children[0]), (50.0, 50.0, 75.0, 50.0));
+        assert_eq!(rect(&root.children[1]), (50.0, 100.0, 300.0, 450.0));
+    }
+
+    #[test]
+    fn weighted_clamped_on_overflow() {
+        let mut root = component(Size::Constant(300.0), Size::Constant(100.0));
+        root.push_child(component(Size::Constant(200.0), Size::Fill));
+        root.push_child(component(Size::Weighted(2.0), Size::Fill));
+        root.push_child(component(Size::Percent(75.0), Size::Fill));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[1]), (200.0, 0.0, 0.0, 100.0));
+        assert_eq!(rect(&root.children[2]), (200.0, 0.0, 225.0, 100.0));
+    }
+
+    #[test]
+    fn clamped_fill_redistributes() {
+        let mut root = component(Size::Constant(600.0), Size::Constant(100.0));
+
+        let mut capped = component(Size::Fill, Size::Fill);
+        capped.data.layout.max_width = 100.0;
+        root.push_child(capped);
+        root.push_child(component(Size::Fill, Size::Fill));
+        let mut floored = component(Size::Weighted(2.0), Size::Fill);
+        floored.data.layout.min_height = 150.0;
+        root.push_child(floored);
+
+        let root = compute(root);
+
+        // 150 each at first, then the 50 freed by the capped child is shared 1:2 by the others
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 100.0, 100.0));
+        assert_eq!(rect(&root.children[1]), (100.0, 0.0, 500.0 / 3.0, 100.0));
+        assert_eq!(
+            rect(&root.children[2]),
+            (100.0 + 500.0 / 3.0, 0.0, 1000.0 / 3.0, 150.0)
+        );
+    }
+
+    #[test]
+    fn min_size_holds_on_shrink() {
+        let mut root = component(Size::Constant(200.0), Size::Constant(100.0));
+        root.data.layout.orientation = Orientation::Column;
+        root.push_child(component(Size::Fill, Size::Constant(80.0)));
+
+        let mut floored = component(Size::Fill, Size::Fill);
+        floored.data.layout.min_height = 50.0;
+        root.push_child(floored);
+        root.push_child(component(Size::Fill, Size::Fill));
+
+        let mut capped = component(Size::Constant(500.0), Size::Constant(500.0));
+        capped.data.layout.max_width = 150.0;
+        capped.data.layout.max_height = 10.0;
+        root.push_child(capped);
+
+        let root = compute(root);
+
+        // the remaining 10 is shared until the floored child hits its min, which then takes 40 more
+        // than there is, leaving nothing for its sibling
+        assert_eq!(rect(&root.children[1]), (0.0, 80.0, 200.0, 50.0));
+        assert_eq!(rect(&root.children[2]), (0.0, 130.0, 200.0, 0.0));
+        assert_eq!(rect(&root.children[3]), (0.0, 130.0, 150.0, 10.0));
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        let mut root = component(Size::Fill, Size::Fill);
+        let mut wide = component(Size::Fill, Size::Fill);
+        wide.data.layout.aspect_ratio = Some(16.0 / 9.0);
+        root.push_child(wide);
+        root.push_child(component(Size::Constant(160.0), Size::Fill));
+
+        let mut column = component(Size::Constant(160.0), Size::Fill);
+        column.data.layout.orientation = Orientation::Column;
+        let mut square = component(Size::Fill, Size::Constant(10.0));
+        square.data.layout.aspect_ratio = Some(1.0);
+        column.push_child(square);
+        column.push_child(component(Size::Fill, Size::Fill));
+        root.push_child(column);
+
+        let root = compute(root);
+        let column = &root.children[2];
+
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 480.0, 270.0));
+        assert_eq!(rect(&column.children[0]), (640.0, 0.0, 160.0, 160.0));
+        assert_eq!(rect(&column.children[1]), (640.0, 160.0, 160.0, 440.0));
+    }
+
+    #[test]
+    fn wrapping_row() {
+        let mut root = component(Size::Constant(300.0), Size::Constant(300.0));
+        root.data.layout.wrap = Wrap::Wrap;
+        root.data.layout.gap = 10.0;
+        root.data.layout.line_gap = 5.0;
+        root.data.layout.align = Align::Centre;
+        root.push_child(component(Size::Constant(120.0), Size::Constant(20.0)));
+        root.push_child(component(Size::Constant(120.0), Size::Constant(40.0)));
+        root.push_child(component(Size::Constant(100.0), Size::Constant(20.0)));
+        let mut stretched = component(Size::Constant(50.0), Size::Constant(10.0));
+        stretched.data.layout.align_self = Some(Align::Stretch);
+        root.push_child(stretched);
+        root.push_child(component(Size::Constant(400.0), Size::Constant(30.0)));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[0]), (0.0, 10.0, 120.0, 20.0));
+        assert_eq!(rect(&root.children[1]), (130.0, 0.0, 120.0, 40.0));
+        assert_eq!(rect(&root.children[2]), (0.0, 45.0, 100.0, 20.0));
+        assert_eq!(rect(&root.children[3]), (110.0, 45.0, 50.0, 20.0));
+        // too large for any line, so placed alone
+        assert_eq!(rect(&root.children[4]), (0.0, 70.0, 400.0, 30.0));
+    }
+
+    #[test]
+    fn wrapping_row_shares_per_line() {
+        let mut root = component(Size::Constant(300.0), Size::Constant(300.0));
+        root.data.layout.wrap = Wrap::Wrap;
+        root.data.layout.justify = Justify::End;
+        root.push_child(component(Size::Constant(200.0), Size::Constant(50.0)));
+        let mut filler = component(Size::Fill, Size::Fill);
+        filler.data.layout.min_width = 50.0;
+        root.push_child(filler);
+        root.push_child(component(Size::Constant(200.0), Size::Constant(30.0)));
+        root.push_child(component(Size::Constant(50.0), Size::Constant(30.0)));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root.children[1]), (200.0, 0.0, 100.0, 50.0));
+        assert_eq!(rect(&root.children[2]), (50.0, 50.0, 200.0, 30.0));
+        assert_eq!(rect(&root.children[3]), (250.0, 50.0, 50.0, 30.0));
+    }
+
+    #[test]
+    fn wrapping_column() {
+        let mut root = component(Size::Constant(400.0), Size::Constant(250.0));
+        root.data.layout.orientation = Orientation::Column;
+        root.data.layout.wrap = Wrap::Wrap;
+        root.data.layout.padding = Padding::uniform(10.0);
+        root.data.layout.gap = 10.0;
+        root.data.layout.line_gap = 20.0;
+        for _ in 0..5 {
+            root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+        }
+
+        let root = compute(root);
+        let positions: Vec<_> = root
+            .children
+            .iter()
+            .map(|c| (rect(c).0, rect(c).1))
+            .collect();
+
+        assert_eq!(
+            positions,
+            vec![
+                (10.0, 10.0),
+                (10.0, 120.0),
+                (130.0, 10.0),
+                (130.0, 120.0),
+                (250.0, 10.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_form() {
+        let mut root = component(Size::Constant(400.0), Size::Fill);
+        root.data.layout.orientation = Orientation::Grid(GridTemplate::new(
+            vec![Track::Fixed(100.0), Track::Fraction(1.0)],
+            vec![Track::Auto],
+        ));
+        root.data.layout.gap = 10.0;
+        root.data.layout.line_gap = 5.0;
+        root.data.layout.padding = Padding::uniform(10.0);
+        root.data.layout.align = Align::Centre;
+
+        for height in [20.0, 30.0, 40.0] {
+            root.push_child(component(Size::Constant(60.0), Size::Constant(20.0)));
+            root.push_child(component(Size::Fill, Size::Constant(height)));
+        }
+
+        let root = compute(root);
+        let rects: Vec<_> = root.children.iter().map(rect).collect();
+
+        assert_eq!(rects[0], (30.0, 10.0, 60.0, 20.0));
+        assert_eq!(rects[1], (120.0, 10.0, 270.0, 20.0));
+        // the implicit rows fit their tallest child, within which the label is centred
+        assert_eq!(rects[2], (30.0, 40.0, 60.0, 20.0));
+        assert_eq!(rects[3], (120.0, 35.0, 270.0, 30.0));
+        assert_eq!(rects[4], (30.0, 80.0, 60.0, 20.0));
+        assert_eq!(rects[5], (120.0, 70.0, 270.0, 40.0));
+    }
+
+    #[test]
+    fn grid_explicit_placement() {
+        let mut root = component(Size::Constant(720.0), Size::Constant(420.0));
+        root.data.layout.orientation = Orientation::Grid(GridTemplate::new(
+            vec![
+                Track::Fraction(1.0),
+                Track::Fraction(2.0),
+                Track::Fixed(100.0),
+            ],
+            vec![
+                Track::Fixed(100.0),
+                Track::MinMax(50.0, 150.0),
+                Track::Fraction(1.0),
+            ],
+        ));
+        root.data.layout.gap = 10.0;
+        root.data.layout.line_gap = 10.0;
+        root.data.layout.align = Align::Stretch;
+
+        // a header across the whole grid, and a sidebar down the last two rows
+        let mut header = component(Size::Fill, Size::Fill);
+        header.data.layout.grid_placement = GridPlacement::new(0, 0).with_span(1, 3);
+        root.push_child(header);
+        let mut sidebar = component(Size::Fill, Size::Fill);
+        sidebar.data.layout.grid_placement = GridPlacement::new(1, 2).with_span(2, 1);
+        root.push_child(sidebar);
+
+        // the rest fill the free cells in order
+        root.push_child(component(Size::Constant(10.0), Size::Constant(80.0)));
+        root.push_child(component(Size::Constant(10.0), Size::Constant(10.0)));
+        let mut bottom = component(Size::Fill, Size::Fill);
+        bottom.data.layout.grid_placement.row = Some(2);
+        root.push_child(bottom);
+
+        let root = compute(root);
+        let rects: Vec<_> = root.children.iter().map(rect).collect();
+
+        assert_eq!(rects[0], (0.0, 0.0, 720.0, 100.0));
+        assert_eq!(rects[1], (620.0, 110.0, 100.0, 310.0));
+        assert_eq!(rects[2], (0.0, 110.0, 200.0, 80.0));
+        assert_eq!(rects[3], (210.0, 110.0, 400.0, 80.0));
+        assert_eq!(rects[4], (0.0, 200.0, 200.0, 220.0));
+    }
+
+    #[test]
+    fn grid_full_row_gains_columns() {
+        let mut root = component(Size::Constant(400.0), Size::Constant(400.0));
+        root.data.layout.orientation = Orientation::Grid(GridTemplate::new(
+            vec![Track::Fixed(100.0), Track::Fixed(100.0)],
+            vec![],
+        ));
+        root.data.layout.gap = 10.0;
+        for _ in 0..3 {
+            let mut cell = component(Size::Constant(50.0), Size::Constant(20.0));
+            cell.data.layout.grid_placement.row = Some(0);
+            root.push_child(cell);
+        }
+        root.push_child(component(Size::Constant(50.0), Size::Constant(20.0)));
+
+        let root = compute(root);
+
+        // the third child stays in its full row, in an implicit column after the last
+        assert_eq!(rect(&root.children[2]), (220.0, 0.0, 50.0, 20.0));
+        // while children placed in order carry on in the next row
+        assert_eq!(rect(&root.children[3]), (0.0, 20.0, 50.0, 20.0));
+    }
+
+    #[test]
+    fn grid_fit_content() {
+        let mut root = component(Size::FitContent, Size::FitContent);
+        root.data.layout.orientation = Orientation::Grid(GridTemplate::new(
+            vec![Track::Auto, Track::MinMax(40.0, 100.0)],
+            vec![],
+        ));
+        root.data.layout.gap = 5.0;
+        root.push_child(component(Size::Constant(70.0), Size::Constant(10.0)));
+        root.push_child(component(Size::Constant(20.0), Size::Constant(30.0)));
+        root.push_child(component(Size::Constant(50.0), Size::Constant(20.0)));
+
+        let root = compute(root);
+
+        assert_eq!(rect(&root), (0.0, 0.0, 115.0, 50.0));
+        assert_eq!(rect(&root.children[1]), (75.0, 0.0, 20.0, 30.0));
+        assert_eq!(rect(&root.children[2]), (0.0, 30.0, 50.0, 20.0));
+    }
+
+    #[test]
+    fn stack_overlay() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Column;
+
+        let mut icon = component(Size::Constant(64.0), Size::Constant(64.0));
+        icon.data.layout.orientation = Orientation::Stack;
+        icon.data.layout.padding = Padding::uniform(4.0);
+        icon.data.layout.align = Align::Centre;
+        icon.push_child(component(Size::Fill, Size::Fill));
+        let mut badge = component(Size::Constant(16.0), Size::Constant(16.0));
+        badge.data.layout.align_self = Some(Align::End);
+        icon.push_child(badge);
+        icon.push_child(component(Size::Constant(20.0), Size::Percent(50.0)));
+        root.push_child(icon);
+
+        let mut panel = component(Size::FitContent, Size::FitContent);
+        panel.data.layout.orientation = Orientation::Stack;
+        panel.data.layout.align = Align::Stretch;
+        panel.push_child(component(Size::Constant(200.0), Size::Constant(50.0)));
+        let mut spinner = component(Size::Constant(30.0), Size::Constant(30.0));
+        spinner.data.layout.align_self = Some(Align::Centre);
+        spinner.data.layout.margin = Margin::uniform(5.0);
+        panel.push_child(spinner);
+        root.push_child(panel);
+
+        let root = compute(root);
+        let icon = &root.children[0];
+        let panel = &root.children[1];
+
+        assert_eq!(rect(&icon.children[0]), (4.0, 4.0, 56.0, 56.0));
+        assert_eq!(rect(&icon.children[1]), (44.0, 44.0, 16.0, 16.0));
+        assert_eq!(rect(&icon.children[2]), (22.0, 18.0, 20.0, 28.0));
+
+        assert_eq!(rect(panel), (0.0, 64.0, 200.0, 50.0));
+        assert_eq!(rect(&panel.children[0]), (0.0, 64.0, 200.0, 50.0));
+        assert_eq!(rect(&panel.children[1]), (85.0, 74.0, 30.0, 30.0));
+    }
+
+    #[test]
+    fn stacking_contexts() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Stack;
+
+        // without a z-index, the child is stacked alongside its parent rather than within it
+        let mut plain = component(Size::Constant(10.0), Size::Constant(10.0));
+        let mut raised = component(Size::Constant(11.0), Size::Constant(11.0));
+        raised.data.layout.z_index = Some(2);
+        plain.push_child(raised);
+        root.push_child(plain);
+
+        // a negative z-index within a stacking context is still above the context itself
+        let mut context = component(Size::Constant(20.0), Size::Constant(20.0));
+        context.data.layout.z_index = Some(1);
+        let mut lowered = component(Size::Constant(21.0), Size::Constant(21.0));
+        lowered.data.layout.z_index = Some(-5);
+        context.push_child(lowered);
+        root.push_child(context);
+
+        let mut below = component(Size::Constant(30.0), Size::Constant(30.0));
+        below.data.layout.z_index = Some(-1);
+        root.push_child(below);
+
+        let root = compute(root);
+        let paint_order = PaintOrder::new(&root);
+        let hit_regions = paint_order.hit_regions();
+        let widths: Vec<f32> = paint_order
+            .iter()
+            .map(|painted| painted.node.data.computed_layout.width)
+            .collect();
+
+        assert_eq!(widths, vec![800.0, 30.0, 10.0, 20.0, 21.0, 11.0]);
+        assert_eq!(hit_regions.hit_test(5.0, 5.0), Some(vec![0, 0]));
+        assert_eq!(hit_regions.hit_test(25.0, 25.0), Some(vec![2]));
+        assert_eq!(hit_regions.hit_test(900.0, 5.0), None);
+    }
+
+    #[test]
+    fn hit_regions_follow_layout() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+
+        components.pointer_moved(50.0, 50.0);
+        assert!(components.layers[0].component_tree.children[0]
+            .data
+            .is_hovered());
+
+        // the regions kept from the last layout are dropped once the layer is laid out again
+        let child = &mut components.layers[0].component_tree.children[0].data;
+        child.layout.width = Size::Constant(10.0);
+        child.relayout();
+        components.compute_layout(SCREEN);
+        components.pointer_moved(50.0, 50.0);
+        let root = &components.layers[0].component_tree;
+        assert!(root.data.is_hovered());
+        assert!(!root.children[0].data.is_hovered());
+    }
+
+    fn scroll_list() -> Node<Component> {
+        let mut list = component(Size::Constant(200.0), Size::Constant(300.0));
+        list.data.layout.orientation = Orientation::Column;
+        list.data.layout.overflow = Overflow::Scroll;
+        list.data.layout.padding = Padding::uniform(10.0);
+        for _ in 0..10 {
+            list.push_child(component(Size::Fill, Size::Constant(100.0)));
+        }
+        list
+    }
+
+    #[test]
+    fn scroll_offset_shifts_and_clamps() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.push_child(scroll_list());
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        let list = |components: &Components| -> (f32, f32) {
+            let list = &components.layers[0].component_tree.children[0];
+            (
+                list.data.computed_layout.scroll.y,
+                list.children[3].data.computed_layout.position.y,
+            )
+        };
+
+        assert_eq!(list(&components), (0.0, 310.0));
+
+        // scrolling down moves the content up
+        assert!(components.scroll(100.0, 100.0, 0.0, -250.0));
+        components.compute_layout(SCREEN);
+        assert_eq!(list(&components), (250.0, 60.0));
+
+        // 1000 of content in a 280 content box can only scroll so far
+        components.scroll(100.0, 100.0, 0.0, -5000.0);
+        components.compute_layout(SCREEN);
+        assert_eq!(list(&components), (720.0, -410.0));
+
+        // outside of the list, nothing scrolls
+        components.scroll(500.0, 100.0, 0.0, 5000.0);
+        components.compute_layout(SCREEN);
+        assert_eq!(list(&components), (720.0, -410.0));
+        assert!(!components.scroll(900.0, 100.0, 0.0, 5000.0));
+    }
+
+    #[test]
+    fn scroll_after_removing_child() {
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: scroll_list(),
+        });
+        components.compute_layout(SCREEN);
+        assert!(components.scroll(100.0, 250.0, 0.0, 0.0));
+
+        // the child under the pointer is gone before the next layout, so its list scrolls in its place
+        components.layers[0].component_tree.children.truncate(2);
+        assert!(components.scroll(100.0, 250.0, 0.0, -100.0));
+        let list = &components.layers[0].component_tree;
+        assert_eq!(list.data.computed_layout.scroll.y, 100.0);
+    }
+
+    #[test]
+    fn nested_scroll_passes_on_remainder() {
+        let mut outer = component(Size::Fill, Size::Constant(400.0));
+        outer.data.layout.orientation = Orientation::Column;
+        outer.data.layout.overflow = Overflow::Scroll;
+        outer.push_child(scroll_list());
+        outer.push_child(component(Size::Fill, Size::Constant(300.0)));
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: outer,
+        });
+        components.compute_layout(SCREEN);
+
+        components.scroll(50.0, 50.0, 0.0, -800.0);
+        components.compute_layout(SCREEN);
+
+        let outer = &components.layers[0].component_tree;
+        assert_eq!(outer.children[0].data.computed_layout.scroll.y, 720.0);
+        assert_eq!(outer.data.computed_layout.scroll.y, 80.0);
+        assert_eq!(rect(&outer.children[0]), (0.0, -80.0, 200.0, 300.0));
+    }
+
+    #[test]
+    fn sticky_headers() {
+        let mut list = component(Size::Constant(200.0), Size::Constant(300.0));
+        list.data.layout.orientation = Orientation::Column;
+        list.data.layout.overflow = Overflow::Scroll;
+        list.data.layout.padding = Padding::uniform(10.0);
+        for _ in 0..2 {
+            let mut section = component(Size::Fill, Size::FitContent);
+            section.data.layout.orientation = Orientation::Column;
+            let mut header = component(Size::Fill, Size::Constant(50.0));
+            header.data.layout.position = Position::Sticky(StickyOffset::top(0.0));
+            section.push_child(header);
+            for _ in 0..3 {
+                section.push_child(component(Size::Fill, Size::Constant(100.0)));
+            }
+            list.push_child(section);
+        }
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: list,
+        });
+        let mut scroll_to = |scroll: f32| -> (f32, f32, f32) {
+            let list = &mut components.layers[0].component_tree;
+            list.data.computed_layout.scroll.y = scroll;
+            list.data.relayout();
+            components.compute_layout(SCREEN);
+
+            let list = &components.layers[0].component_tree;
+            (
+                list.children[0].data.computed_layout.position.y,
+                list.children[0].children[0].data.computed_layout.position.y,
+                list.children[1].children[0].data.computed_layout.position.y,
+            )
+        };
+
+        // in the flow until scrolled past the top of the list
+        assert_eq!(scroll_to(0.0), (10.0, 10.0, 360.0));
+        assert_eq!(scroll_to(200.0), (-190.0, 10.0, 160.0));
+        // pushed up by the end of the first section, until the next header takes its place
+        assert_eq!(scroll_to(320.0), (-310.0, -10.0, 40.0));
+        assert_eq!(scroll_to(400.0), (-390.0, -90.0, 10.0));
+    }
+
+    #[test]
+    fn sticky_header_scrolls_sideways() {
+        let mut table = component(Size::Constant(200.0), Size::Constant(300.0));
+        table.data.layout.orientation = Orientation::Column;
+        table.data.layout.overflow = Overflow::Scroll;
+        let mut header = component(Size::Constant(600.0), Size::Constant(50.0));
+        header.data.layout.position = Position::Sticky(StickyOffset::top(0.0));
+        table.push_child(header);
+        for _ in 0..5 {
+            table.push_child(component(Size::Constant(600.0), Size::Constant(100.0)));
+        }
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: table,
+        });
+        components.compute_layout(SCREEN);
+        let table = &mut components.layers[0].component_tree;
+        table.data.computed_layout.scroll = Point { x: 100.0, y: 200.0 };
+        table.data.relayout();
+        components.compute_layout(SCREEN);
+
+        // the header is pinned to the top, but scrolls sideways along with its columns
+        let table = &components.layers[0].component_tree;
+        assert_eq!(rect(&table.children[0]), (-100.0, 0.0, 600.0, 50.0));
+        assert_eq!(rect(&table.children[1]), (-100.0, -150.0, 600.0, 100.0));
+    }
+
+    #[test]
+    fn hidden_overflow_does_not_scroll() {
+        let mut list = scroll_list();
+        list.data.layout.overflow = Overflow::Hidden;
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: list,
+        });
+        components.compute_layout(SCREEN);
+        components.scroll(50.0, 50.0, 0.0, -100.0);
+        components.compute_layout(SCREEN);
+
+        let list = &components.layers[0].component_tree;
+        assert_eq!(list.data.computed_layout.content_height, 1000.0);
+        assert_eq!(list.data.computed_layout.scroll.y, 0.0);
+        assert_eq!(rect(&list.children[1]), (10.0, 110.0, 180.0, 100.0));
+    }
+
+    #[test]
+    fn relayout_only_dirty_subtree() {
+        let mut root = component(Size::Fill, Size::Fill);
+        let mut sidebar = component(Size::Constant(100.0), Size::Constant(100.0));
+        sidebar.push_child(component(Size::Constant(20.0), Size::Constant(20.0)));
+        root.push_child(sidebar);
+        let mut panel = component(Size::FitContent, Size::FitContent);
+        panel.push_child(component(Size::Constant(50.0), Size::Constant(50.0)));
+        root.push_child(panel);
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.mark_painted();
+        assert!(!components.is_dirty());
+
+        // a change that isn't marked is not picked up, as nothing is laid out again
+        let root = &mut components.layers[0].component_tree;
+        root.children[0].children[0].data.layout.width = Size::Constant(40.0);
+        root.children[1].children[0].data.layout.width = Size::Constant(80.0);
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[1]), (100.0, 0.0, 50.0, 50.0));
+
+        // the panel grows around its content, which moves its later sibling but not its earlier one,
+        // so nothing within the earlier sibling is laid out again
+        let root = &mut components.layers[0].component_tree;
+        root.children[1].children[0].data.relayout();
+        assert!(components.is_dirty());
+        components.compute_layout(SCREEN);
+
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 100.0, 100.0));
+        assert_eq!(rect(&root.children[0].children[0]), (0.0, 0.0, 20.0, 20.0));
+        assert_eq!(rect(&root.children[1]), (100.0, 0.0, 80.0, 50.0));
+        assert_eq!(rect(&root.children[2]), (180.0, 0.0, 100.0, 100.0));
+        assert!(!root.children[0].data.dirty.needs_paint());
+        assert!(root.children[1].data.dirty.paint);
+        assert!(root.children[2].data.dirty.paint);
+        assert!(!root.data.dirty.needs_layout());
+
+        components.mark_painted();
+        assert!(!components.is_dirty());
+    }
+
+    #[test]
+    fn resize_only_touches_screen_dependants() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+        root.push_child(component(Size::Fill, Size::Constant(100.0)));
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.mark_painted();
+
+        // same size, nothing to do
+        components.compute_layout(SCREEN);
+        assert!(!components.is_dirty());
+
+        components.compute_layout(Dimensions {
+            width: 1000.0,
+            height: 600.0,
+        });
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(root), (0.0, 0.0, 1000.0, 600.0));
+        assert_eq!(rect(&root.children[1]), (100.0, 0.0, 900.0, 100.0));
+        assert!(root.data.dirty.paint);
+        assert!(!root.children[0].data.dirty.needs_paint());
+        assert!(root.children[1].data.dirty.paint);
+
+        // a fixed root doesn't depend on the screen at all
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: component(Size::Constant(100.0), Size::Constant(100.0)),
+        });
+        components.compute_layout(SCREEN);
+        components.mark_painted();
+        components.compute_layout(Dimensions {
+            width: 1000.0,
+            height: 600.0,
+        });
+        assert!(!components.is_dirty());
+    }
+
+    #[test]
+    fn dump_layout() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.id = Some("root".to_string());
+        let mut sidebar = component(Size::Constant(100.0), Size::Fill);
+        sidebar.data.id = Some("sidebar".to_string());
+        sidebar.push_child(component(Size::Fill, Size::Percent(12.5)));
+        root.push_child(sidebar);
+        root.push_child(component(Size::Weighted(2.0), Size::Constant(50.5)));
+        root.push_child(component(Size::Fill, Size::Constant(50.5)));
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.layers.push(Layer {
+            component_tree: component(Size::Constant(10.0), Size::Constant(10.0)),
+        });
+        components.compute_layout(SCREEN);
+
+        assert_eq!(
+            components.dump(),
+            "layer 0
+  #root (0, 0) 800x600
+    #sidebar (0, 0) 100x600
+      [0] (0, 0) 100x75
+    [1] (100, 0) 466.66666x50.5
+    [2] (566.6666, 0) 233.33333x50.5
+layer 1
+  [0] (0, 0) 10x10
+"
+        );
+        assert_eq!(components.layers[1].dump(), "[0] (0, 0) 10x10\n");
+    }
+
+    #[test]
+    fn diagnose_layout() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.id = Some("root".to_string());
+        root.push_child(component(Size::Constant(500.0), Size::Fill));
+        root.push_child(component(Size::Constant(400.0), Size::Fill));
+        let mut panel = component(Size::Constant(50.0), Size::Constant(50.0));
+        panel.data.id = Some("panel".to_string());
+        panel.data.layout.padding = Padding::uniform(40.0);
+        panel.push_child(component(Size::Constant(f32::INFINITY), Size::Fill));
+        root.push_child(panel);
+        let mut popup = component(Size::Constant(10.0), Size::Constant(10.0));
+        popup.data.layout.position = Position::Absolute(Offset::new(-20.0, 0.0), Anchor::TopLeft);
+        root.push_child(popup);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+
+        assert_eq!(
+            components.diagnostics(),
+            &[
+                Diagnostic::Overflow {
+                    path: "layer 0: #root".to_string(),
+                    width: 150.0,
+                    height: 0.0,
+                },
+                Diagnostic::Overflow {
+                    path: "layer 0: #root/#panel".to_string(),
+                    width: f32::INFINITY,
+                    height: 30.0,
+                },
+                Diagnostic::InvalidSize {
+                    path: "layer 0: #root/#panel/[0]".to_string(),
+                    width: f32::INFINITY,
+                    height: 0.0,
+                },
+                Diagnostic::OutsideContainingBlock {
+                    path: "layer 0: #root/[3]".to_string(),
+                },
+            ]
+        );
+
+        // once the layout is fixed, nothing is left to report
+        let root = &mut components.layers[0].component_tree;
+        root.children[0].data.layout.width = Size::Constant(300.0);
+        root.children[2].data.layout.padding = Padding::uniform(10.0);
+        root.children[2].children[0].data.layout.width = Size::Fill;
+        root.children[2].children[0].data.relayout();
+        root.children[3].data.layout.position =
+            Position::Absolute(Offset::new(20.0, 0.0), Anchor::TopLeft);
+        for child in &mut root.children {
+            child.data.relayout();
+        }
+        components.compute_layout(SCREEN);
+        assert!(components.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn relative_units() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.padding = Padding::uniform(Length::Rem(1.0));
+        let mut label = component(
+            Size::Length(Length::Em(5.0)),
+            Size::Length(Length::Vh(10.0)),
+        );
+        label.data.style.font_size = Some(20.0);
+        root.push_child(label);
+        let mut shifted = component(Size::Constant(50.0), Size::Length(Length::Percent(50.0)));
+        shifted.data.layout.position = Position::Relative(Offset::new(Length::Vw(10.0), 0.0));
+        root.push_child(shifted);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[0]), (16.0, 16.0, 100.0, 60.0));
+        assert_eq!(rect(&root.children[1]), (196.0, 16.0, 50.0, 284.0));
+
+        // the root font size and the window are picked up without marking anything
+        components.mark_painted();
+        components.set_root_font_size(20.0);
+        components.compute_layout(Dimensions {
+            width: 1000.0,
+            height: 600.0,
+        });
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[0]), (20.0, 20.0, 100.0, 60.0));
+        assert_eq!(rect(&root.children[1]), (220.0, 20.0, 50.0, 280.0));
+    }
+
+    #[test]
+    fn constraint_layout() {
+        let constraints = vec![
+            Constraint::equal(
+                Attribute::child(0, Edge::Left),
+                Attribute::parent(Edge::Left),
+            ),
+            Constraint::equal(
+                Attribute::child(1, Edge::Left),
+                Attribute::child(0, Edge::Right),
+            )
+            .plus(8.0),
+            Constraint::equal(
+                Attribute::child(1, Edge::Width),
+                Attribute::child(0, Edge::Width),
+            ),
+            Constraint::equal(
+                Attribute::child(1, Edge::Right),
+                Attribute::parent(Edge::Right),
+            ),
+            Constraint::fixed(Attribute::child(1, Edge::Height), 100.0)
+                .with_strength(Strength::Strong),
+        ];
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Constraints(constraints.clone());
+        root.push_child(component(Size::Constant(100.0), Size::Fill));
+        root.push_child(component(Size::Constant(300.0), Size::Fill));
+
+        let root = compute(root);
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 396.0, 600.0));
+        assert_eq!(rect(&root.children[1]), (404.0, 0.0, 396.0, 100.0));
+
+        // fitting the content leaves the children as close to their own sizes as the constraints allow
+        let mut root = component(Size::FitContent, Size::Fill);
+        root.data.layout.orientation = Orientation::Constraints(constraints);
+        root.push_child(component(Size::Constant(100.0), Size::Fill));
+        root.push_child(component(Size::Constant(300.0), Size::Fill));
+
+        let root = compute(root);
+        assert_eq!(rect(&root), (0.0, 0.0, 208.0, 600.0));
+        assert_eq!(rect(&root.children[1]), (108.0, 0.0, 100.0, 100.0));
+
+        // margins are kept clear around each child, within the edges the constraints place
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Constraints(vec![
+            Constraint::equal(
+                Attribute::child(0, Edge::Left),
+                Attribute::parent(Edge::Left),
+            ),
+            Constraint::equal(
+                Attribute::child(1, Edge::Left),
+                Attribute::child(0, Edge::Right),
+            ),
+        ]);
+        let mut first = component(Size::Constant(100.0), Size::Constant(50.0));
+        first.data.layout.margin = Margin::new(5.0, 10.0, 5.0, 20.0);
+        root.push_child(first);
+        let mut second = component(Size::Constant(100.0), Size::Constant(50.0));
+        second.data.layout.margin = Margin::uniform(4.0);
+        root.push_child(second);
+
+        let root = compute(root);
+        assert_eq!(rect(&root.children[0]), (20.0, 5.0, 100.0, 50.0));
+        assert_eq!(rect(&root.children[1]), (134.0, 4.0, 100.0, 50.0));
+
+        // conflicting constraints, and those referring to children that aren't there, are left out
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.layout.orientation = Orientation::Constraints(vec![
+            Constraint::fixed(Attribute::child(0, Edge::Width), 50.0),
+            Constraint::fixed(Attribute::child(0, Edge::Width), 60.0),
+            Constraint::at_least(
+                Attribute::child(0, Edge::Top),
+                Attribute::child(1, Edge::Bottom),
+            ),
+        ]);
+        root.push_child(component(Size::Constant(100.0), Size::Constant(100.0)));
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 50.0, 100.0));
+        assert_eq!(
+            components.diagnostics(),
+            &[
+                Diagnostic::UnsatisfiableConstraint {
+                    path: "layer 0: [0]".to_string(),
+                    index: 1,
+                },
+                Diagnostic::UnsatisfiableConstraint {
+                    path: "layer 0: [0]".to_string(),
+                    index: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cascade_style() {
+        let mut root = component(Size::Fill, Size::Fill);
+        root.data.style.font_size = Some(20.0);
+        root.data.style.font_colour = Some(Colour::new(1.0, 0.0, 0.0));
+        root.data.style.background_colour = Some(Colour::new(0.0, 0.0, 1.0));
+        let mut panel = component(Size::Fill, Size::Fill);
+        let mut label = component(Size::FitContent, Size::FitContent);
+        label.data.style.font_weight = Some(400.0);
+        label.data.measure = Some(Box::new(|component: &Component| Dimensions {
+            width: 5.0 * component.computed_style.font_size,
+            height: component.computed_style.font_size,
+        }));
+        panel.push_child(label);
+        root.push_child(panel);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+
+        // the font is inherited, unless overridden, whereas the background is not
+        let panel = &components.layers[0].component_tree.children[0];
+        let label = &panel.children[0];
+        assert_eq!(panel.data.computed_style.font_size, 20.0);
+        assert_eq!(
+            panel.data.computed_style.background_colour,
+            LIGHT.background_colour
+        );
+        assert_eq!(
+            label.data.computed_style.font_colour,
+            Colour::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(label.data.computed_style.font_weight, 400.0);
+        assert_eq!(label.data.computed_style.font_size, 20.0);
+        assert_eq!(rect(label), (0.0, 0.0, 100.0, 20.0));
+
+        // changing an inherited font lays out again whatever it is inherited by
+        components.mark_painted();
+        let root = &mut components.layers[0].component_tree.data;
+        root.set_style(Style {
+            font_size: Some(10.0),
+            ..root.style.clone()
+        });
+        components.compute_layout(SCREEN);
+        let label = &components.layers[0].component_tree.children[0].children[0];
+        assert!(label.data.dirty.paint);
+        assert_eq!(rect(label), (0.0, 0.0, 50.0, 10.0));
+
+        // clean subtrees are left alone, until they are marked to be restyled
+        components.mark_painted();
+        let label = &mut components.layers[0].component_tree.children[0].children[0];
+        label.data.style.font_weight = Some(700.0);
+        components.compute_layout(SCREEN);
+        let label = &mut components.layers[0].component_tree.children[0].children[0];
+        assert_eq!(label.data.computed_style.font_weight, 400.0);
+        label.data.restyle();
+        assert!(components.is_dirty());
+        components.compute_layout(SCREEN);
+        let label = &components.layers[0].component_tree.children[0].children[0];
+        assert_eq!(label.data.computed_style.font_weight, 700.0);
+        assert!(label.data.dirty.paint);
+    }
+
+    #[test]
+    fn switch_theme() {
+        let mut root = component(Size::Fill, Size::Fill);
+        let mut panel = component(Size::Fill, Size::Fill);
+        panel.data.style.background_colour = Some(Colour::new(0.5, 0.5, 0.5));
+        panel.push_child(component(Size::Constant(10.0), Size::Constant(10.0)));
+        root.push_child(panel);
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.mark_painted();
+
+        components.set_theme(Theme::dark());
+        assert!(components.theme().is_dark());
+        assert!(components.is_dirty());
+        components.compute_layout(SCREEN);
+
+        // overrides are kept, while everything else is restyled and painted again
+        let root = &components.layers[0].component_tree;
+        let panel = &root.children[0];
+        let leaf = &panel.children[0];
+        assert_eq!(root.data.computed_style.background_colour, Colour::black());
+        assert_eq!(leaf.data.computed_style.font_colour, Colour::white());
+        assert_eq!(
+            panel.data.computed_style.background_colour,
+            Colour::new(0.5, 0.5, 0.5)
+        );
+        assert!(root.data.dirty.paint);
+        assert!(leaf.data.dirty.paint);
+    }
+
+    #[test]
+    fn apply_rules() {
+        // components that leave their width as it initially is take it from the rules they match
+        let initial_width = |height: f32| {
+            let mut node = Node::new(Component::new());
+            node.data.layout.height = Size::Constant(height);
+            node
+        };
+        let mut root = component(Size::Fill, Size::Fill);
+        let mut button = initial_width(10.0);
+        button.data.tag = Some("button".to_string());
+        button.data.classes = vec!["primary".to_string()];
+        let mut submit = initial_width(10.0);
+        submit.data.tag = Some("button".to_string());
+        submit.data.id = Some("submit".to_string());
+        submit.data.classes = vec!["primary".to_string()];
+        submit.data.style.font_size = Some(24.0);
+        let mut fixed = component(Size::Constant(42.0), Size::Constant(10.0));
+        fixed.data.tag = Some("button".to_string());
+        root.push_child(button);
+        root.push_child(submit);
+        root.push_child(fixed);
+
+        let rules = Rules::parse(
+            "#submit { width: 5rem; font-weight: 700; }
+            .primary { background-colour: #f00; font-size: 20px; }
+            button { background-colour: #00f; width: 50%; padding: 4px 8px; }",
+        )
+        .unwrap();
+        let mut components = Components::new();
+        components.set_rules(rules);
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+
+        // more specific rules apply over less specific ones, and overrides apply over both
+        let root = &components.layers[0].component_tree;
+        let (button, submit) = (&root.children[0], &root.children[1]);
+        assert_eq!(rect(button), (0.0, 0.0, 400.0, 10.0));
+        assert_eq!(rect(submit), (400.0, 0.0, 80.0, 10.0));
+        assert_eq!(button.data.computed_layout.padding.left, 8.0);
+        assert_eq!(
+            button.data.computed_style.background_colour,
+            Colour::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(button.data.computed_style.font_size, 20.0);
+        assert_eq!(submit.data.computed_style.font_size, 24.0);
+        assert_eq!(submit.data.computed_style.font_weight, 700.0);
+
+        // layout set on the component itself applies over the rules, which still apply elsewhere
+        let fixed = &root.children[2];
+        assert_eq!(rect(fixed), (480.0, 0.0, 42.0, 10.0));
+        assert_eq!(fixed.data.computed_layout.padding.left, 8.0);
+        assert!(fixed.data.layout.padding == Layout::default().padding);
+
+        // a component that no longer has a class no longer matches its rules
+        let button = &mut components.layers[0].component_tree.children[0].data;
+        button.classes.clear();
+        button.restyle();
+        components.compute_layout(SCREEN);
+        let button = &components.layers[0].component_tree.children[0];
+        assert_eq!(
+            button.data.computed_style.background_colour,
+            Colour::new(0.0, 0.0, 1.0)
+        );
+        assert!(button.data.dirty.paint);
+
+        // and the layout it took from those rules reverts, to that of the rules it still matches or
+        // to its own
+        let submit = &mut components.layers[0].component_tree.children[1].data;
+        submit.id = None;
+        submit.restyle();
+        components.compute_layout(SCREEN);
+        let submit = &components.layers[0].component_tree.children[1];
+        assert_eq!(rect(submit), (400.0, 0.0, 400.0, 10.0));
+
+        let button = &mut components.layers[0].component_tree.children[0].data;
+        button.tag = None;
+        button.restyle();
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert_eq!(rect(&root.children[0]), (0.0, 0.0, 300.0, 10.0));
+        assert_eq!(root.children[0].data.computed_layout.padding.left, 0.0);
+        assert_eq!(rect(&root.children[1]), (300.0, 0.0, 400.0, 10.0));
+    }
+
+    #[test]
+    fn match_rules_once() {
+        let mut root = component(Size::Fill, Size::Fill);
+        let mut button = component(Size::Constant(10.0), Size::Constant(10.0));
+        button.data.classes = vec!["primary".to_string()];
+        root.push_child(button);
+
+        let mut components = Components::new();
+        components.set_rules(Rules::parse(".primary { opacity: 0.5; }").unwrap());
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        let matched = |components: &Components| {
+            let button = &components.layers[0].component_tree.children[0].data;
+            button.matched_rules.as_ref().unwrap().indices.as_ptr()
+        };
+        let before = matched(&components);
+
+        // a component cascaded again for the style of its parent keeps the rules it matched
+        let root = &mut components.layers[0].component_tree.data;
+        root.set_style(Style {
+            font_size: Some(20.0),
+            ..Style::default()
+        });
+        components.compute_layout(SCREEN);
+        let button = &components.layers[0].component_tree.children[0].data;
+        assert_eq!(button.computed_style.font_size, 20.0);
+        assert_eq!(button.computed_style.opacity, 0.5);
+        assert_eq!(matched(&components), before);
+
+        // but matches them again once its classes change
+        let button = &mut components.layers[0].component_tree.children[0].data;
+        button.classes.clear();
+        button.restyle();
+        components.compute_layout(SCREEN);
+        let button = &components.layers[0].component_tree.children[0].data;
+        assert_eq!(button.computed_style.opacity, 1.0);
+        assert!(button.matched_rules.as_ref().unwrap().indices.is_empty());
+    }
+
+    #[test]
+    fn interaction_states() {
+        let mut root = component(Size::Fill, Size::Fill);
+        for _ in 0..3 {
+            let mut button = component(Size::Constant(100.0), Size::Constant(50.0));
+            button.data.focusable = true;
+            button.data.style.background_colour = Some(Colour::white());
+            let states = &mut button.data.state_styles;
+            states.hovered.background_colour = Some(Colour::new(0.0, 0.0, 1.0));
+            states.pressed.background_colour = Some(Colour::new(0.0, 1.0, 0.0));
+            states.focused.border_colour = Some(Colour::new(1.0, 0.0, 0.0));
+            states.disabled.background_colour = Some(Colour::transparent());
+            root.push_child(button);
+        }
+        root.children[2].data.disabled = true;
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.mark_painted();
+        let style = |components: &Components, i: usize| {
+            components.layers[0].component_tree.children[i]
+                .data
+                .computed_style
+                .clone()
+        };
+
+        // the hovered component is painted again, with its ancestors hovered along with it
+        components.pointer_moved(150.0, 10.0);
+        assert!(components.is_dirty());
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert!(root.data.is_hovered() && root.children[1].data.is_hovered());
+        assert!(!root.children[0].data.is_hovered());
+        assert_eq!(
+            style(&components, 1).background_colour,
+            Colour::new(0.0, 0.0, 1.0)
+        );
+        assert_eq!(style(&components, 0).background_colour, Colour::white());
+
+        // pressing takes precedence over hovering, and focuses the pressed component
+        components.pointer_input(150.0, 10.0, true);
+        components.compute_layout(SCREEN);
+        assert_eq!(
+            style(&components, 1).background_colour,
+            Colour::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            style(&components, 1).border_colour,
+            Colour::new(1.0, 0.0, 0.0)
+        );
+        components.pointer_input(150.0, 10.0, false);
+        components.pointer_left();
+        components.compute_layout(SCREEN);
+        assert_eq!(style(&components, 1).background_colour, Colour::white());
+
+        // focus skips disabled components, wrapping around at the end
+        components.key_input(Key::Tab, true, false);
+        let root = &components.layers[0].component_tree;
+        assert!(root.children[0].data.is_focused());
+        assert!(!root.children[1].data.is_focused());
+        components.key_input(Key::Tab, true, true);
+        assert!(components.layers[0].component_tree.children[1]
+            .data
+            .is_focused());
+
+        // the focused component is pressed from the keyboard
+        components.key_input(Key::Space, true, false);
+        components.compute_layout(SCREEN);
+        assert_eq!(
+            style(&components, 1).background_colour,
+            Colour::new(0.0, 1.0, 0.0)
+        );
+        components.key_input(Key::Space, false, false);
+
+        // only the overrides for being disabled apply to a disabled component
+        components.pointer_moved(250.0, 10.0);
+        components.compute_layout(SCREEN);
+        assert_eq!(
+            style(&components, 2).background_colour,
+            Colour::transparent()
+        );
+    }
+
+    #[test]
+    fn stale_interaction_states() {
+        let mut root = component(Size::Fill, Size::Fill);
+        for _ in 0..3 {
+            let mut button = component(Size::Constant(100.0), Size::Constant(50.0));
+            button.data.focusable = true;
+            button.data.style.background_colour = Some(Colour::white());
+            button.data.state_styles.hovered.background_colour = Some(Colour::new(0.0, 0.0, 1.0));
+            root.push_child(button);
+        }
+
+        let mut components = Components::new();
+        components.layers.push(Layer {
+            component_tree: root,
+        });
+        components.compute_layout(SCREEN);
+        components.pointer_moved(150.0, 10.0);
+        components.compute_layout(SCREEN);
+
+        // a hovered component that moved in the tree is no longer hovered, nor left hovered once the
+        // pointer moves on
+        let root = &mut components.layers[0].component_tree;
+        root.children.remove(0);
+        root.data.relayout();
+        components.compute_layout(SCREEN);
+        components.pointer_moved(500.0, 300.0);
+        components.compute_layout(SCREEN);
+        let root = &components.layers[0].component_tree;
+        assert!(root.data.is_hovered());
+        assert!(root.children.iter().all(|child| !child.data.is_hovered()));
+        assert_eq!(
+            root.children[0].data.computed_style.background_colour,
+            Colour::white()
+        );
+
+        // a focused component that is disabled loses focus
+        components.key_input(Key::Tab, true, false);
+        let button = &mut components.layers[0].component_tree.children[0].data;
+        assert!(button.is_focused());
+        button.disabled = true;
+        button.restyle();
+        components.compute_layout(SCREEN);
+        assert!(components.focused.is_none());
+        assert!(!components.layers[0].component_tree.children[0]
+            .data
+            .is_focused());
+        components.key_input(Key::Tab, true, false);
+        assert!(components.layers[0].component_tree.children[1]
+            .data
+            .is_focused());
+    }
 }
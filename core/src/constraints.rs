@@ -0,0 +1,317 @@
+use cassowary::{
+    strength, Constraint as SolverConstraint, Expression, RelationalOperator, Solver, Term,
+    Variable,
+};
+
+use crate::{
+    component::{Component, Components},
+    layout::{
+        Attribute, ComputedConstraints, Edge, Item, Orientation, Point, Position, Rect, Relation,
+        Strength,
+    },
+    tree::Node,
+};
+
+/// The size each child asks for is kept between `Medium` and `Weak` constraints
+const SIZE_STRENGTH: f64 = strength::MEDIUM / 2.0;
+/// Where each child would sit otherwise is weaker than any constraint
+const POSITION_STRENGTH: f64 = strength::WEAK / 2.0;
+
+/// The variables of the box of either a child or the parent, relative to the content box of the parent.
+/// The box of a child includes its margins, so that they are kept clear around it
+#[derive(Clone, Copy)]
+struct Frame {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable,
+}
+
+impl Frame {
+    fn new() -> Self {
+        Self {
+            left: Variable::new(),
+            top: Variable::new(),
+            width: Variable::new(),
+            height: Variable::new(),
+        }
+    }
+
+    /// Returns the given edge or size of the box as a sum of its variables
+    fn terms(&self, edge: Edge) -> Vec<Term> {
+        let term = |variable, coefficient| Term {
+            variable,
+            coefficient,
+        };
+        match edge {
+            Edge::Left => vec![term(self.left, 1.0)],
+            Edge::Right => vec![term(self.left, 1.0), term(self.width, 1.0)],
+            Edge::CentreX => vec![term(self.left, 1.0), term(self.width, 0.5)],
+            Edge::Top => vec![term(self.top, 1.0)],
+            Edge::Bottom => vec![term(self.top, 1.0), term(self.height, 1.0)],
+            Edge::CentreY => vec![term(self.top, 1.0), term(self.height, 0.5)],
+            Edge::Width => vec![term(self.width, 1.0)],
+            Edge::Height => vec![term(self.height, 1.0)],
+        }
+    }
+}
+
+/// Resolves the width and height of the children of the given node by solving its constraints,
+/// alongside the boxes they are placed in
+pub(crate) fn resolve_child_dimensions(comp_tree: &mut Node<Component>) {
+    let parent_padding = &comp_tree.data.computed_layout.padding;
+    let parent_width = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+    let parent_height = comp_tree.data.computed_layout.height - parent_padding.vertical();
+
+    let solved = solve(comp_tree, Some((parent_width, parent_height)));
+    for (child, frame) in comp_tree.children.iter_mut().zip(&solved.frames) {
+        // absolutely positioned children are sized against their containing block afterwards
+        if let Some(frame) = frame {
+            let margin = &child.data.effective_layout.margin;
+            child.data.computed_layout.width = frame.width - margin.horizontal();
+            child.data.computed_layout.height = frame.height - margin.vertical();
+        }
+    }
+
+    comp_tree.data.computed_layout.constraints = solved;
+}
+
+/// Resolves the screen position of the children of the given node, from the boxes they were placed in
+/// when their dimensions were resolved
+pub(crate) fn resolve_child_positions(comp_tree: &mut Node<Component>) {
+    let parent_padding = &comp_tree.data.computed_layout.padding;
+    // children are placed within the content box, i.e. the parent's box less its padding
+    let parent_pos = &Point {
+        x: comp_tree.data.computed_layout.position.x + parent_padding.left,
+        y: comp_tree.data.computed_layout.position.y + parent_padding.top,
+    };
+    let parent_w = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+    let parent_h = comp_tree.data.computed_layout.height - parent_padding.vertical();
+    let frames = &comp_tree.data.computed_layout.constraints.frames;
+
+    for (child, frame) in comp_tree.children.iter_mut().zip(frames) {
+        // absolutely positioned children are placed in their containing block afterwards
+        let Some(frame) = frame else {
+            continue;
+        };
+
+        let (offset_x, offset_y) = match &child.data.effective_layout.position {
+            Position::Relative(offset) => {
+                offset.resolve(parent_w, parent_h, &child.data.computed_layout.units)
+            }
+            _ => (0.0, 0.0),
+        };
+        let margin = &child.data.effective_layout.margin;
+        child.data.computed_layout.position = Point {
+            x: parent_pos.x + frame.x + margin.left + offset_x,
+            y: parent_pos.y + frame.y + margin.top + offset_y,
+        };
+    }
+}
+
+/// Returns the intrinsic width and height of the content of the given node, which is the extent of its
+/// children when the constraints are solved with the content box as small as they allow
+pub(crate) fn measure_content(comp_tree: &Node<Component>) -> (f32, f32) {
+    let solved = solve(comp_tree, None);
+    solved
+        .frames
+        .iter()
+        .flatten()
+        .fold((0.0, 0.0), |(width, height), frame| {
+            (
+                f32::max(width, frame.x + frame.width),
+                f32::max(height, frame.y + frame.height),
+            )
+        })
+}
+
+/// Solves the constraints of the given node for the boxes of its children, within a content box of
+/// the given size, or otherwise one that is as small as the constraints allow.
+///
+/// Each child asks for the size it would take along the cross axis of a `Row` or `Column`, and to
+/// sit at the top-left of the content box, both of which give way to the constraints
+fn solve(comp_tree: &Node<Component>, content: Option<(f32, f32)>) -> ComputedConstraints {
+    let Orientation::Constraints(constraints) = &comp_tree.data.effective_layout.orientation else {
+        unreachable!("only constraint layouts are solved as constraints");
+    };
+
+    let mut solver = Solver::new();
+    let mut add = |expression: Expression, relation: RelationalOperator, strength: f64| {
+        solver
+            .add_constraint(SolverConstraint::new(expression, relation, strength))
+            .is_ok()
+    };
+    let expression = |terms: Vec<Term>, constant: f32| Expression::new(terms, constant as f64);
+
+    // the content box is at the top-left, and is only as large as the constraints require when its
+    // size is not given
+    let parent = Frame::new();
+    let (width, height, size_strength) = match content {
+        Some((width, height)) => (width, height, strength::REQUIRED),
+        None => (0.0, 0.0, POSITION_STRENGTH),
+    };
+    for (edge, value, strength) in [
+        (Edge::Left, 0.0, strength::REQUIRED),
+        (Edge::Top, 0.0, strength::REQUIRED),
+        (Edge::Width, width, size_strength),
+        (Edge::Height, height, size_strength),
+    ] {
+        add(
+            expression(parent.terms(edge), -value),
+            RelationalOperator::Equal,
+            strength,
+        );
+    }
+
+    let frames: Vec<Option<Frame>> = comp_tree
+        .children
+        .iter()
+        .map(
+            |child| match child.data.effective_layout.position.is_absolute() {
+                true => None,
+                false => Some(Frame::new()),
+            },
+        )
+        .collect();
+
+    for (child, frame) in comp_tree.children.iter().zip(&frames) {
+        let Some(frame) = frame else {
+            continue;
+        };
+
+        let layout = &child.data.effective_layout;
+        let computed = &child.data.computed_layout;
+        let size = |edge: Edge| match (edge, content) {
+            (Edge::Width, Some((width, _))) => Components::resolve_cross_size(
+                &layout.width,
+                width,
+                layout.margin.horizontal(),
+                computed.intrinsic_width,
+                false,
+                &computed.units,
+            ),
+            (Edge::Height, Some((_, height))) => Components::resolve_cross_size(
+                &layout.height,
+                height,
+                layout.margin.vertical(),
+                computed.intrinsic_height,
+                false,
+                &computed.units,
+            ),
+            // relative sizes can't be known until the parent is sized, so fall back to the content
+            (Edge::Width, None) => computed.intrinsic_width,
+            _ => computed.intrinsic_height,
+        };
+
+        // sizes are of the box of the child itself, which the frame holds along with its margins
+        for (edge, min, max, margin) in [
+            (
+                Edge::Width,
+                layout.min_width,
+                layout.max_width,
+                layout.margin.horizontal(),
+            ),
+            (
+                Edge::Height,
+                layout.min_height,
+                layout.max_height,
+                layout.margin.vertical(),
+            ),
+        ] {
+            let size = size(edge);
+            add(
+                expression(frame.terms(edge), -(size + margin)),
+                RelationalOperator::Equal,
+                SIZE_STRENGTH,
+            );
+            add(
+                expression(frame.terms(edge), -(min.max(0.0) + margin)),
+                RelationalOperator::GreaterOrEqual,
+                strength::REQUIRED,
+            );
+            if max.is_finite() {
+                add(
+                    expression(frame.terms(edge), -(max + margin)),
+                    RelationalOperator::LessOrEqual,
+                    strength::REQUIRED,
+                );
+            }
+        }
+        if let Some(ratio) = layout.aspect_ratio {
+            let mut terms = frame.terms(Edge::Width);
+            terms.push(Term {
+                variable: frame.height,
+                coefficient: -ratio as f64,
+            });
+            // i.e. `width - horizontal margin = ratio * (height - vertical margin)`
+            let constant = ratio * layout.margin.vertical() - layout.margin.horizontal();
+            add(
+                expression(terms, constant),
+                RelationalOperator::Equal,
+                strength::REQUIRED,
+            );
+        }
+
+        for edge in [Edge::Left, Edge::Top] {
+            add(
+                expression(frame.terms(edge), 0.0),
+                RelationalOperator::Equal,
+                POSITION_STRENGTH,
+            );
+        }
+    }
+
+    let attribute_terms = |attribute: &Attribute| match attribute.item {
+        Item::Parent => Some(parent.terms(attribute.edge)),
+        Item::Child(index) => frames.get(index)?.map(|frame| frame.terms(attribute.edge)),
+    };
+    let mut unsatisfiable = Vec::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        let Some(mut terms) = attribute_terms(&constraint.attribute) else {
+            unsatisfiable.push(i);
+            continue;
+        };
+        // the other side is moved across, i.e. `attribute - multiplier * other - constant`
+        if let Some(other) = &constraint.other {
+            let Some(other) = attribute_terms(other) else {
+                unsatisfiable.push(i);
+                continue;
+            };
+            terms.extend(other.into_iter().map(|term| Term {
+                coefficient: -term.coefficient * constraint.multiplier as f64,
+                ..term
+            }));
+        }
+
+        let relation = match constraint.relation {
+            Relation::Equal => RelationalOperator::Equal,
+            Relation::LessOrEqual => RelationalOperator::LessOrEqual,
+            Relation::GreaterOrEqual => RelationalOperator::GreaterOrEqual,
+        };
+        let strength = match constraint.strength {
+            Strength::Required => strength::REQUIRED,
+            Strength::Strong => strength::STRONG,
+            Strength::Medium => strength::MEDIUM,
+            Strength::Weak => strength::WEAK,
+        };
+        if !add(expression(terms, -constraint.constant), relation, strength) {
+            unsatisfiable.push(i);
+        }
+    }
+
+    let value = |variable| solver.get_value(variable) as f32;
+    ComputedConstraints {
+        frames: frames
+            .iter()
+            .map(|frame| {
+                frame.map(|frame| Rect {
+                    x: value(frame.left),
+                    y: value(frame.top),
+                    width: value(frame.width),
+                    height: value(frame.height),
+                })
+            })
+            .collect(),
+        unsatisfiable,
+    }
+}
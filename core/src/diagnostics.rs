@@ -0,0 +1,155 @@
+use std::fmt;
+
+use crate::{component::Component, layout::Overflow, tree::Node};
+
+/// Differences smaller than this are put down to rounding, rather than reported
+const TOLERANCE: f32 = 0.01;
+
+/// A problem with a computed layout, which is likely to be a mistake in the layout it was computed
+/// from. Each names the component it was found on by its path from the root of its layer, e.g.
+/// `layer 0: #root/[1]/#sidebar`, where components without an id are given by their index amongst
+/// their siblings
+#[derive(Clone, PartialEq, Debug)]
+pub enum Diagnostic {
+    /// The flowed children of the component take up more room than its content box has, by the given
+    /// width and height, and it neither clips nor scrolls them. This includes padding that is larger
+    /// than the component itself
+    Overflow {
+        path: String,
+        width: f32,
+        height: f32,
+    },
+    /// The component was given a width or height that is negative, infinite or NaN, such as from a
+    /// negative min size or an infinite constant size
+    InvalidSize {
+        path: String,
+        width: f32,
+        height: f32,
+    },
+    /// The absolutely positioned component is not wholly within the box it is positioned in
+    OutsideContainingBlock { path: String },
+    /// The `Required` constraint of the given index, amongst those the component lays out its
+    /// children by, conflicts with the others, or refers to a child that isn't laid out by them
+    UnsatisfiableConstraint { path: String, index: usize },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Overflow {
+                path,
+                width,
+                height,
+            } => write!(f, "{path}: children overflow by {width}x{height}"),
+            Diagnostic::InvalidSize {
+                path,
+                width,
+                height,
+            } => write!(f, "{path}: invalid size {width}x{height}"),
+            Diagnostic::OutsideContainingBlock { path } => {
+                write!(f, "{path}: outside of its containing block")
+            }
+            Diagnostic::UnsatisfiableConstraint { path, index } => {
+                write!(f, "{path}: constraint {index} can't be satisfied")
+            }
+        }
+    }
+}
+
+/// Checks the computed layout of the tree of the given root, in the layer of the given index
+pub(crate) fn diagnose(layer: usize, root: &Node<Component>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let path = Path {
+        layer,
+        parent: None,
+        component: &root.data,
+        index: 0,
+    };
+    diagnose_node(root, &path, true, &mut diagnostics);
+    diagnostics
+}
+
+/// The path to a node from the root of its layer, which is only written out for the nodes that
+/// diagnostics are found on
+struct Path<'a> {
+    layer: usize,
+    parent: Option<&'a Path<'a>>,
+    component: &'a Component,
+    index: usize,
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.parent {
+            Some(parent) => write!(f, "{parent}/")?,
+            None => write!(f, "layer {}: ", self.layer)?,
+        }
+        write!(f, "{}", self.component.label(self.index))
+    }
+}
+
+fn diagnose_node(
+    node: &Node<Component>,
+    path: &Path,
+    is_root: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let layout = &node.data.effective_layout;
+    let computed = &node.data.computed_layout;
+
+    let is_valid = |size: f32| size.is_finite() && size >= 0.0;
+    if !is_valid(computed.width) || !is_valid(computed.height) {
+        diagnostics.push(Diagnostic::InvalidSize {
+            path: path.to_string(),
+            width: computed.width,
+            height: computed.height,
+        });
+    }
+
+    // the root is positioned in the screen, which it is not given as a containing block
+    if layout.position.is_absolute() && !is_root {
+        let rect = computed.rect();
+        // each node is given the containing block of its parent, which is the box it is placed in
+        let containing = &computed.containing_block;
+        if rect.x < containing.x - TOLERANCE
+            || rect.y < containing.y - TOLERANCE
+            || rect.x + rect.width > containing.x + containing.width + TOLERANCE
+            || rect.y + rect.height > containing.y + containing.height + TOLERANCE
+        {
+            diagnostics.push(Diagnostic::OutsideContainingBlock {
+                path: path.to_string(),
+            });
+        }
+    }
+
+    if matches!(layout.overflow, Overflow::Visible) {
+        let width = computed.content_width - (computed.width - computed.padding.horizontal());
+        let height = computed.content_height - (computed.height - computed.padding.vertical());
+        if width > TOLERANCE || height > TOLERANCE {
+            diagnostics.push(Diagnostic::Overflow {
+                path: path.to_string(),
+                width: width.max(0.0),
+                height: height.max(0.0),
+            });
+        }
+    }
+
+    if layout.orientation.is_constraints() {
+        for index in &computed.constraints.unsatisfiable {
+            diagnostics.push(Diagnostic::UnsatisfiableConstraint {
+                path: path.to_string(),
+                index: *index,
+            });
+        }
+    }
+
+    for (i, child) in node.children.iter().enumerate() {
+        let path = Path {
+            layer: path.layer,
+            parent: Some(path),
+            component: &child.data,
+            index: i,
+        };
+        diagnose_node(child, &path, false, diagnostics);
+    }
+}
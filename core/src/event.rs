@@ -7,14 +7,45 @@ pub enum Event {
 
 pub enum PointerEvent {
     Touch,
-    MouseMoved,
+    /// The pointer was moved to the given position in pixels
+    MouseMoved {
+        x: f32,
+        y: f32,
+    },
     MouseEntered,
     MouseExited,
-    MouseInput
+    /// The given button was pressed, or released, while the pointer was at the given position
+    MouseInput {
+        x: f32,
+        y: f32,
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// The mouse wheel, or touchpad, was scrolled by the given delta in pixels while the pointer was at
+    /// the given position. Positive deltas scroll towards the start of the content, i.e. up or left
+    MouseWheel {
+        x: f32,
+        y: f32,
+        delta_x: f32,
+        delta_y: f32,
+    },
+}
+
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other,
 }
 
 pub enum WindowEvent {
-    Resized,
+    /// The drawable area of the window was resized to the given size in physical pixels, or moved to
+    /// a display with a different scale factor
+    Resized {
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+    },
     Moved,
     CloseRequested,
     FocusChanged,
@@ -23,7 +54,21 @@ pub enum WindowEvent {
 }
 
 pub enum KeyboardEvent {
-    KeyboardInput,
+    /// The given key was pressed, or released, where `shift` is whether either shift key was held
+    KeyboardInput {
+        key: Key,
+        pressed: bool,
+        shift: bool,
+    },
+}
+
+/// The keys that are handled by components, where any other key is `Other`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Key {
+    Tab,
+    Enter,
+    Space,
+    Other,
 }
 
 pub enum ClipboardEvent {
@@ -0,0 +1,424 @@
+use crate::{
+    component::{Component, Components},
+    layout::{ComputedGrid, GridArea, GridTemplate, Orientation, Point, Position, Track},
+    tree::Node,
+};
+
+/// Resolves the width and height of the children of the given grid node.
+///
+/// Children are first placed in the cells of the grid, then the tracks are sized to fit the children
+/// placed in only one track, before the space left over is shared between the `Fraction` tracks.
+/// Each child is then sized within the area of the cells it spans, much like the cross axis of a
+/// `Row` or `Column`, and aligned within it on both axes
+pub(crate) fn resolve_child_dimensions(comp_tree: &mut Node<Component>) {
+    let Orientation::Grid(template) = &comp_tree.data.effective_layout.orientation else {
+        unreachable!("only grids have their children resolved as a grid");
+    };
+
+    let parent_padding = &comp_tree.data.computed_layout.padding;
+    let parent_width = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+    let parent_height = comp_tree.data.computed_layout.height - parent_padding.vertical();
+    let parent_align = &comp_tree.data.effective_layout.align;
+    let column_gap = comp_tree.data.effective_layout.gap;
+    let row_gap = comp_tree.data.effective_layout.line_gap;
+
+    let (areas, column_count, row_count) = place(template, &comp_tree.children);
+    let (column_content, row_content) =
+        content_sizes(&comp_tree.children, &areas, column_count, row_count);
+    let columns = size_tracks(
+        &template.columns,
+        &column_content,
+        Some(parent_width),
+        column_gap,
+    );
+    let rows = size_tracks(&template.rows, &row_content, Some(parent_height), row_gap);
+
+    for (child, area) in comp_tree.children.iter_mut().zip(&areas) {
+        let layout = &child.data.effective_layout;
+        let computed = &mut child.data.computed_layout;
+        let align = layout.align_self.as_ref().unwrap_or(parent_align);
+
+        // absolutely positioned children are sized against their containing block afterwards
+        let Some(area) = area else {
+            continue;
+        };
+        let area_width = span_size(&columns, area.column, area.column_span, column_gap);
+        let area_height = span_size(&rows, area.row, area.row_span, row_gap);
+        let stretch = matches!(align, crate::layout::Align::Stretch);
+
+        computed.width = layout.clamp_width(Components::resolve_cross_size(
+            &layout.width,
+            area_width,
+            layout.margin.horizontal(),
+            computed.intrinsic_width,
+            stretch,
+            &computed.units,
+        ));
+        computed.height = layout.clamp_height(match layout.aspect_ratio {
+            Some(ratio) => computed.width / ratio,
+            None => Components::resolve_cross_size(
+                &layout.height,
+                area_height,
+                layout.margin.vertical(),
+                computed.intrinsic_height,
+                stretch,
+                &computed.units,
+            ),
+        });
+    }
+
+    comp_tree.data.computed_layout.grid = ComputedGrid {
+        columns,
+        rows,
+        areas,
+    };
+}
+
+/// Resolves the screen position of the children of the given grid node, by aligning each within the
+/// area of the cells it spans.
+/// This process is contigent on the tracks and cells resolved alongside the dimensions of the children
+pub(crate) fn resolve_child_positions(comp_tree: &mut Node<Component>) {
+    let parent_padding = &comp_tree.data.computed_layout.padding;
+    // children are placed within the content box, i.e. the parent's box less its padding
+    let parent_pos = &Point {
+        x: comp_tree.data.computed_layout.position.x + parent_padding.left,
+        y: comp_tree.data.computed_layout.position.y + parent_padding.top,
+    };
+    let parent_w = comp_tree.data.computed_layout.width - parent_padding.horizontal();
+    let parent_h = comp_tree.data.computed_layout.height - parent_padding.vertical();
+    let parent_align = &comp_tree.data.effective_layout.align;
+    let column_gap = comp_tree.data.effective_layout.gap;
+    let row_gap = comp_tree.data.effective_layout.line_gap;
+    let grid = &comp_tree.data.computed_layout.grid;
+
+    for (child, area) in comp_tree.children.iter_mut().zip(&grid.areas) {
+        // absolutely positioned children are placed in their containing block afterwards
+        let Some(area) = area else {
+            continue;
+        };
+
+        let (offset_x, offset_y) = match &child.data.effective_layout.position {
+            Position::Relative(offset) => {
+                offset.resolve(parent_w, parent_h, &child.data.computed_layout.units)
+            }
+            _ => (0.0, 0.0),
+        };
+        let margin = &child.data.effective_layout.margin;
+        let align = child
+            .data
+            .effective_layout
+            .align_self
+            .as_ref()
+            .unwrap_or(parent_align);
+        let area_width = span_size(&grid.columns, area.column, area.column_span, column_gap);
+        let area_height = span_size(&grid.rows, area.row, area.row_span, row_gap);
+
+        child.data.computed_layout.position = Point {
+            x: parent_pos.x
+                + offset_x
+                + track_offset(&grid.columns, area.column, column_gap)
+                + align.offset(
+                    area_width,
+                    child.data.computed_layout.width,
+                    margin.left,
+                    margin.right,
+                ),
+            y: parent_pos.y
+                + offset_y
+                + track_offset(&grid.rows, area.row, row_gap)
+                + align.offset(
+                    area_height,
+                    child.data.computed_layout.height,
+                    margin.top,
+                    margin.bottom,
+                ),
+        };
+    }
+}
+
+/// Returns the intrinsic width and height of the content of the given grid node, where every track is
+/// only as large as the children placed in it
+pub(crate) fn measure_content(comp_tree: &Node<Component>) -> (f32, f32) {
+    let Orientation::Grid(template) = &comp_tree.data.effective_layout.orientation else {
+        unreachable!("only grids are measured as a grid");
+    };
+
+    let (areas, column_count, row_count) = place(template, &comp_tree.children);
+    let (column_content, row_content) =
+        content_sizes(&comp_tree.children, &areas, column_count, row_count);
+    let columns = size_tracks(&template.columns, &column_content, None, 0.0);
+    let rows = size_tracks(&template.rows, &row_content, None, 0.0);
+
+    (
+        span_size(
+            &columns,
+            0,
+            columns.len(),
+            comp_tree.data.effective_layout.gap,
+        ),
+        span_size(
+            &rows,
+            0,
+            rows.len(),
+            comp_tree.data.effective_layout.line_gap,
+        ),
+    )
+}
+
+/// Places the children of a grid in its cells, returning the area each child spans along with the
+/// number of columns and rows, including any implicit tracks.
+///
+/// Children with both a row and column are placed first. The rest are then placed in order, in the
+/// next free cell after the previous child placed this way, searching along each row in turn. A child
+/// given only a row stays in that row, which gains implicit columns when it is full
+fn place(
+    template: &GridTemplate,
+    children: &[Node<Component>],
+) -> (Vec<Option<GridArea>>, usize, usize) {
+    let mut column_count = template.columns.len().max(1);
+    for child in children {
+        let placement = &child.data.effective_layout.grid_placement;
+        if let Some(column) = placement.column {
+            column_count = column_count.max(column + placement.column_span.max(1));
+        }
+    }
+
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    // rows may be shorter than others where implicit columns were added after they were occupied
+    let occupy = |occupied: &mut Vec<Vec<bool>>, area: &GridArea| {
+        if occupied.len() < area.row + area.row_span {
+            occupied.resize(area.row + area.row_span, Vec::new());
+        }
+        let end = area.column + area.column_span;
+        for row in &mut occupied[area.row..area.row + area.row_span] {
+            if row.len() < end {
+                row.resize(end, false);
+            }
+            row[area.column..end].fill(true);
+        }
+    };
+    let is_free = |occupied: &Vec<Vec<bool>>, area: &GridArea| {
+        (area.row..area.row + area.row_span).all(|row| {
+            (area.column..area.column + area.column_span).all(|column| {
+                !occupied
+                    .get(row)
+                    .and_then(|cells| cells.get(column))
+                    .is_some_and(|cell| *cell)
+            })
+        })
+    };
+
+    let mut areas = vec![None; children.len()];
+
+    for (i, child) in children.iter().enumerate() {
+        let placement = &child.data.effective_layout.grid_placement;
+        if let (Some(row), Some(column), false) = (
+            placement.row,
+            placement.column,
+            child.data.effective_layout.position.is_absolute(),
+        ) {
+            let area = GridArea {
+                row,
+                column,
+                row_span: placement.row_span.max(1),
+                column_span: placement.column_span.max(1),
+            };
+            occupy(&mut occupied, &area);
+            areas[i] = Some(area);
+        }
+    }
+
+    let mut cursor = (0, 0);
+    for (i, child) in children.iter().enumerate() {
+        let placement = &child.data.effective_layout.grid_placement;
+        if child.data.effective_layout.position.is_absolute() || areas[i].is_some() {
+            continue;
+        }
+
+        let column_span = placement.column_span.clamp(1, column_count);
+        let mut area = GridArea {
+            row: placement.row.unwrap_or(cursor.0),
+            column: placement.column.unwrap_or(0),
+            row_span: placement.row_span.max(1),
+            column_span,
+        };
+
+        match (placement.row, placement.column) {
+            // a fixed row is searched from its start, and is never left, so once it is full the child
+            // is placed in implicit columns after the last
+            (Some(_), None) => {
+                area.column = 0;
+                while !is_free(&occupied, &area) {
+                    area.column += 1;
+                }
+                column_count = column_count.max(area.column + column_span);
+            }
+            // a fixed column is searched from the first row
+            (None, Some(_)) => {
+                area.row = 0;
+                while !is_free(&occupied, &area) {
+                    area.row += 1;
+                }
+            }
+            _ => {
+                area.row = cursor.0;
+                area.column = cursor.1;
+                loop {
+                    if area.column + column_span > column_count {
+                        area.column = 0;
+                        area.row += 1;
+                    } else if is_free(&occupied, &area) {
+                        break;
+                    } else {
+                        area.column += 1;
+                    }
+                }
+                cursor = (area.row, area.column + column_span);
+            }
+        }
+
+        occupy(&mut occupied, &area);
+        areas[i] = Some(area);
+    }
+
+    let row_count = occupied.len().max(template.rows.len());
+    (areas, column_count, row_count)
+}
+
+/// Returns the size of the largest child placed in only that track, for each column and row
+fn content_sizes(
+    children: &[Node<Component>],
+    areas: &[Option<GridArea>],
+    column_count: usize,
+    row_count: usize,
+) -> (Vec<f32>, Vec<f32>) {
+    let mut columns = vec![0.0f32; column_count];
+    let mut rows = vec![0.0f32; row_count];
+
+    for (child, area) in children.iter().zip(areas) {
+        let Some(area) = area else {
+            continue;
+        };
+
+        let margin = &child.data.effective_layout.margin;
+        let computed = &child.data.computed_layout;
+        if area.column_span == 1 {
+            columns[area.column] =
+                columns[area.column].max(computed.intrinsic_width + margin.horizontal());
+        }
+        if area.row_span == 1 {
+            rows[area.row] = rows[area.row].max(computed.intrinsic_height + margin.vertical());
+        }
+    }
+
+    (columns, rows)
+}
+
+/// Sizes each track given the size of its content. Tracks beyond those defined are implicit `Auto`
+/// tracks.
+///
+/// When there is no `available` space, the tracks are sized intrinsically, so `Fraction` tracks fit
+/// their content like `Auto` tracks
+fn size_tracks(tracks: &[Track], content: &[f32], available: Option<f32>, gap: f32) -> Vec<f32> {
+    let track = |i: usize| tracks.get(i).unwrap_or(&Track::Auto);
+
+    let mut sizes: Vec<f32> = content
+        .iter()
+        .enumerate()
+        .map(|(i, content)| match track(i) {
+            Track::Fixed(size) => *size,
+            Track::Fraction(_) if available.is_some() => 0.0,
+            Track::Fraction(_) | Track::Auto => *content,
+            Track::MinMax(min, max) => content.min(*max).max(*min),
+        })
+        .collect();
+
+    let Some(available) = available else {
+        return sizes;
+    };
+
+    let total_gap = gap * sizes.len().saturating_sub(1) as f32;
+    let mut free = available - sizes.iter().sum::<f32>() - total_gap;
+    let total_fraction: f32 = (0..sizes.len())
+        .filter_map(|i| match track(i) {
+            Track::Fraction(fraction) => Some(*fraction),
+            _ => None,
+        })
+        .sum();
+
+    if total_fraction > 0.0 {
+        // if the other tracks already overflow, there is nothing left to share
+        let free = free.max(0.0);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if let Track::Fraction(fraction) = track(i) {
+                *size = free * fraction / total_fraction;
+            }
+        }
+        return sizes;
+    }
+
+    // share what is left between the tracks that can still grow, until none can or there is none left,
+    // or the shares are too small to grow any track at its precision
+    while free > f32::EPSILON {
+        let growable: Vec<usize> = (0..sizes.len())
+            .filter(|&i| matches!(track(i), Track::MinMax(_, max) if sizes[i] < *max))
+            .collect();
+        if growable.is_empty() {
+            break;
+        }
+
+        let share = free / growable.len() as f32;
+        let before = free;
+        for i in growable {
+            let Track::MinMax(_, max) = track(i) else {
+                continue;
+            };
+            let grown = (sizes[i] + share).min(*max);
+            free -= grown - sizes[i];
+            sizes[i] = grown;
+        }
+        if free >= before {
+            break;
+        }
+    }
+
+    sizes
+}
+
+/// Returns the size of the given span of tracks, including the gaps between them
+fn span_size(tracks: &[f32], start: usize, span: usize, gap: f32) -> f32 {
+    let end = (start + span).min(tracks.len());
+    if start >= end {
+        return 0.0;
+    }
+
+    tracks[start..end].iter().sum::<f32>() + gap * (end - start - 1) as f32
+}
+
+/// Returns the offset of the given track from the start of the grid
+fn track_offset(tracks: &[f32], index: usize, gap: f32) -> f32 {
+    tracks[..index.min(tracks.len())]
+        .iter()
+        .map(|size| size + gap)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grow_tracks_past_float_precision() {
+        // shares too small to change the size of a track must not keep the tracks growing forever
+        let tracks = vec![Track::MinMax(0.0, 1500.0); 4];
+        let content = [93.33, 199.67, 47.0, 90.67];
+        for (available, gap) in [(823.13, 1.857), (600.0, 3.3)] {
+            let sizes = size_tracks(&tracks, &content, Some(available), gap);
+            let total = sizes.iter().sum::<f32>() + gap * 3.0;
+            assert!((total - available).abs() < 0.01);
+            for (size, content) in sizes.iter().zip(content) {
+                assert!(*size >= content);
+            }
+        }
+    }
+}
@@ -1,9 +1,110 @@
+#[derive(Clone)]
 pub struct Layout {
     pub(crate) orientation: Orientation,
     pub(crate) position: Position,
     pub(crate) width: Size,
     pub(crate) height: Size,
     pub(crate) padding: Padding,
+    /// Space kept clear around this element, outside of its box, when placed in the flow
+    pub(crate) margin: Margin,
+    /// Space between each of the flowed children of this element, or between the columns of a grid
+    pub(crate) gap: f32,
+    /// How flowed children are distributed along the main axis of this element
+    pub(crate) justify: Justify,
+    /// How flowed children are placed along the cross axis of this element
+    pub(crate) align: Align,
+    /// Overrides the `align` of the parent for this element only
+    pub(crate) align_self: Option<Align>,
+    pub(crate) min_width: f32,
+    pub(crate) max_width: f32,
+    pub(crate) min_height: f32,
+    pub(crate) max_height: f32,
+    /// The ratio of width to height. When set, the height is derived from the width, and the
+    /// requested height is ignored
+    pub(crate) aspect_ratio: Option<f32>,
+    /// Whether flowed children that overflow the main axis of this element start a new line
+    pub(crate) wrap: Wrap,
+    /// Space between each line of children when wrapping, or between the rows of a grid
+    pub(crate) line_gap: f32,
+    /// The cell of the parent grid in which this element is placed
+    pub(crate) grid_placement: GridPlacement,
+    /// How children that overflow the box of this element are treated
+    pub(crate) overflow: Overflow,
+    /// The direction in which this element and its descendants are laid out, where `None` inherits
+    /// the direction of the parent
+    pub(crate) direction: Option<Direction>,
+    /// The order in which this element is painted, and hit, relative to the others in the same
+    /// stacking context, where higher values are on top. When set, this element forms a stacking
+    /// context of its own, within which its descendants are ordered, and otherwise it is at zero
+    pub(crate) z_index: Option<i32>,
+}
+
+impl Default for Layout {
+    /// The initial layout of every component, which rules only override where it is left as is
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::Row,
+            position: Position::Static,
+            height: Size::Constant(200.0),
+            width: Size::Constant(300.0),
+            padding: Padding::none(),
+            margin: Margin::none(),
+            gap: 0.0,
+            justify: Justify::Start,
+            align: Align::Start,
+            align_self: None,
+            min_width: 0.0,
+            max_width: f32::INFINITY,
+            min_height: 0.0,
+            max_height: f32::INFINITY,
+            aspect_ratio: None,
+            wrap: Wrap::NoWrap,
+            line_gap: 0.0,
+            grid_placement: GridPlacement::auto(),
+            overflow: Overflow::Visible,
+            direction: None,
+            z_index: None,
+        }
+    }
+}
+
+impl Layout {
+    /// Sets the width and height the element asks for. The component must be marked to be laid out
+    /// again once this changes
+    pub fn set_size(&mut self, width: Size, height: Size) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Whether any length of this element, such as its padding or offset, resolves to a different
+    /// number of pixels against the given units than against the previous ones
+    pub(crate) fn depends_on(&self, previous: &Units, units: &Units) -> bool {
+        let changed = |length: &Length| length.resolve(0.0, previous) != length.resolve(0.0, units);
+        let sizes = [&self.width, &self.height]
+            .into_iter()
+            .any(|size| match size {
+                Size::Length(length) => changed(length),
+                _ => false,
+            });
+        let offset = match &self.position {
+            Position::Static => false,
+            Position::Relative(offset) | Position::Absolute(offset, _) => {
+                changed(&offset.x) || changed(&offset.y)
+            }
+            Position::Sticky(offset) => [&offset.x, &offset.y].into_iter().flatten().any(changed),
+        };
+        sizes || offset || self.padding.resolve(previous) != self.padding.resolve(units)
+    }
+
+    /// Clamps the given width between the min and max width, where the min width takes precedence
+    pub(crate) fn clamp_width(&self, width: f32) -> f32 {
+        width.min(self.max_width).max(self.min_width)
+    }
+
+    /// Clamps the given height between the min and max height, where the min height takes precedence
+    pub(crate) fn clamp_height(&self, height: f32) -> f32 {
+        height.min(self.max_height).max(self.min_height)
+    }
 }
 
 #[derive(Default)]
@@ -12,24 +113,530 @@ pub struct ComputedLayout {
     pub(crate) position: Point,
     pub(crate) width: f32,
     pub(crate) height: f32,
+    /// the padding of this quad in pixels, resolved from its layout against its units
+    pub(crate) padding: ComputedPadding,
+    /// what the relative lengths of this quad were resolved against when it was last laid out
+    pub(crate) units: Units,
+    /// the size this quad would take if it were only as large as its content
+    pub(crate) intrinsic_width: f32,
+    pub(crate) intrinsic_height: f32,
+    /// the box its absolutely positioned descendants are placed in, as given by its nearest positioned
+    /// ancestor, which it passes on unless it is positioned itself
+    pub(crate) containing_block: Rect,
+    /// the box its sticky descendants are kept within, as given by its nearest scroll container, which
+    /// it passes on unless it is a scroll container itself
+    pub(crate) scrollport: Rect,
+    /// the lines that the children of this quad were broken into
+    pub(crate) lines: Vec<FlowLine>,
+    /// the tracks of this quad, and the cells its children were placed in, when it is a grid
+    pub(crate) grid: ComputedGrid,
+    /// the boxes its children were placed in, when it is laid out by constraints
+    pub(crate) constraints: ComputedConstraints,
+    /// the extent of the children of this quad, from the top-left of its content box
+    pub(crate) content_width: f32,
+    pub(crate) content_height: f32,
+    /// the direction this quad was laid out in, as resolved from its ancestors
+    pub(crate) direction: Direction,
+    /// how far the content of this quad is scrolled, when it is a scroll container. Unlike the rest of
+    /// the computed layout, this persists between layouts
+    pub(crate) scroll: Point,
 }
 
-#[derive(Clone)]
+impl ComputedLayout {
+    /// Returns how far the content of this quad may be scrolled on each axis, such that the end of the
+    /// content lines up with the end of its content box
+    pub(crate) fn max_scroll(&self) -> (f32, f32) {
+        (
+            f32::max(
+                self.content_width - (self.width - self.padding.horizontal()),
+                0.0,
+            ),
+            f32::max(
+                self.content_height - (self.height - self.padding.vertical()),
+                0.0,
+            ),
+        )
+    }
+
+    /// Returns the box the content of this quad is laid out in, i.e. its box less its padding, moved
+    /// along with the content by its scroll offset. Both are mirrored when laid out from right to left
+    pub(crate) fn content_box(&self) -> Rect {
+        let scroll_x = match self.direction.is_rtl() {
+            true => -self.scroll.x,
+            false => self.scroll.x,
+        };
+        let scrollport = self.scrollport();
+        Rect {
+            x: scrollport.x - scroll_x,
+            y: scrollport.y - self.scroll.y,
+            ..scrollport
+        }
+    }
+
+    /// Returns the part of the content box of this quad through which its content is seen, which,
+    /// unlike the content box, stays in place when the content is scrolled
+    pub(crate) fn scrollport(&self) -> Rect {
+        let padding = &self.padding;
+        let start = match self.direction.is_rtl() {
+            true => padding.right,
+            false => padding.left,
+        };
+        Rect {
+            x: self.position.x + start,
+            y: self.position.y + padding.top,
+            width: self.width - padding.horizontal(),
+            height: self.height - padding.vertical(),
+        }
+    }
+
+    /// Returns the box of this quad in screenspace
+    pub fn rect(&self) -> Rect {
+        Rect {
+            x: self.position.x,
+            y: self.position.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ComputedGrid {
+    pub(crate) columns: Vec<f32>,
+    pub(crate) rows: Vec<f32>,
+    /// the cells each child was placed in, where absolutely positioned children have none
+    pub(crate) areas: Vec<Option<GridArea>>,
+}
+
+#[derive(Default)]
+pub struct ComputedConstraints {
+    /// the box of each child, including its margins, from the top-left of the content box, where
+    /// absolutely positioned children have none
+    pub(crate) frames: Vec<Option<Rect>>,
+    /// the indices of the `Required` constraints that couldn't be kept, or which refer to children
+    /// that are not laid out by the constraints
+    pub(crate) unsatisfiable: Vec<usize>,
+}
+
+#[derive(Clone, Copy)]
+pub struct GridArea {
+    pub(crate) row: usize,
+    pub(crate) column: usize,
+    pub(crate) row_span: usize,
+    pub(crate) column_span: usize,
+}
+
+/// A line of flowed children, given as the range of their indices within their parent
+#[derive(Default)]
+pub struct FlowLine {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    /// the size of the line along the cross axis, within which its children are aligned
+    pub(crate) cross_size: f32,
+}
+
+impl ComputedLayout {
+    /// Returns the width of this quad along a row, or the height along a column
+    pub(crate) fn main_size(&self, is_row: bool) -> f32 {
+        if is_row {
+            self.width
+        } else {
+            self.height
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub enum Size {
     Constant(f32),
+    /// `Percent` is a percentage of the content box of the parent, where `100.0` is all of it
+    Percent(f32),
+    /// `Fill` shares the space left over by its siblings equally with the other filling elements, and
+    /// is the same as `Weighted(1.0)`
     Fill,
+    /// `Weighted` shares the space left over by its siblings in proportion to its weight, e.g. weights
+    /// of `1.0`, `2.0` and `1.0` split the space in a 1:2:1 ratio. Along the cross axis it fills the
+    /// parent like `Fill`
+    Weighted(f32),
+    /// `FitContent` shrink-wraps the element around its flowed children, or the size given by its
+    /// measure hook, plus its padding
+    FitContent,
+    /// `Length` is a size in any unit, e.g. `Size::Length(Length::Rem(2.0))`, which is resolved to
+    /// pixels during layout, and is otherwise the same as `Constant`. A percentage is the same as
+    /// `Percent`
+    Length(Length),
 }
 
 /// Determines how children of this element are positioned
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum Orientation {
-    /// `Row` means that children are placed side-by-side, from left to right, in the order they are defined
+    /// `Row` means that children are placed side-by-side, from left to right, in the order they are defined.
+    /// When laid out from right to left, they are placed from right to left instead
     Row,
     /// `Column` means that children are placed vertically, from top to bottom, in the order they are defined
     Column,
+    /// `Grid` means that children are placed in the cells of a grid, made up of the given row and column
+    /// tracks. Each child is placed by its `GridPlacement`, or otherwise in the next free cell along
+    /// the rows, adding further rows as needed
+    Grid(GridTemplate),
+    /// `Stack` means that children are placed over one another, each within the whole of this element,
+    /// and are painted in the order they are defined
+    Stack,
+    /// `Constraints` means that children are placed by solving the given linear constraints between
+    /// their edges and sizes, and those of the content box of this element, e.g. that the left edge of
+    /// one child equals the right edge of another plus 8. The edges of a child are those of its
+    /// margins, which are kept clear around it. Where the constraints leave a child free, it keeps to
+    /// its own size, at the top-left of this element
+    Constraints(Vec<Constraint>),
+}
+
+impl Orientation {
+    /// Whether children are flowed from left to right, as opposed to from top to bottom
+    pub(crate) fn is_row(&self) -> bool {
+        matches!(self, Orientation::Row)
+    }
+
+    pub(crate) fn is_grid(&self) -> bool {
+        matches!(self, Orientation::Grid(_))
+    }
+
+    pub(crate) fn is_stack(&self) -> bool {
+        matches!(self, Orientation::Stack)
+    }
+
+    pub(crate) fn is_constraints(&self) -> bool {
+        matches!(self, Orientation::Constraints(_))
+    }
+}
+
+/// The row and column tracks of a grid. Children placed beyond the defined tracks are given implicit
+/// `Auto` tracks
+#[derive(Clone, PartialEq)]
+pub struct GridTemplate {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+}
+
+impl GridTemplate {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self { columns, rows }
+    }
+}
+
+/// Determines the size of a single row or column of a grid
+#[derive(Clone, PartialEq)]
+pub enum Track {
+    /// `Fixed` is a constant size
+    Fixed(f32),
+    /// `Fraction` shares the space left over by the other tracks in proportion to its weight, like
+    /// `Size::Weighted`
+    Fraction(f32),
+    /// `Auto` fits the largest child placed in only this track
+    Auto,
+    /// `MinMax` fits the largest child placed in only this track, within the given min and max
+    /// sizes. If there are no `Fraction` tracks, any space left over is shared between these tracks,
+    /// up to their max sizes
+    MinMax(f32, f32),
+}
+
+/// Determines the cell of a grid in which this element is placed, where rows and columns count from
+/// zero. If either the row or column is not given, the element is placed in the next free cell. An
+/// element given only a row is always placed in that row, where implicit `Auto` columns are added
+/// after the last once the row is full
+#[derive(Clone)]
+pub struct GridPlacement {
+    pub row: Option<usize>,
+    pub column: Option<usize>,
+    pub row_span: usize,
+    pub column_span: usize,
+}
+
+impl GridPlacement {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row: Some(row),
+            column: Some(column),
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+
+    pub const fn auto() -> Self {
+        Self {
+            row: None,
+            column: None,
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+
+    pub fn with_span(self, row_span: usize, column_span: usize) -> Self {
+        Self {
+            row_span,
+            column_span,
+            ..self
+        }
+    }
+}
+
+/// A linear relation between an attribute of a child of a constraint layout and another attribute, of
+/// the form `attribute = multiplier * other + constant`, or an inequality of the same form. It is kept
+/// as far as its strength allows, where a `Required` constraint that can't be kept is left out
+#[derive(Clone, PartialEq)]
+pub struct Constraint {
+    pub(crate) attribute: Attribute,
+    pub(crate) relation: Relation,
+    pub(crate) other: Option<Attribute>,
+    pub(crate) multiplier: f32,
+    pub(crate) constant: f32,
+    pub(crate) strength: Strength,
+}
+
+impl Constraint {
+    /// `attribute = other`
+    pub fn equal(attribute: Attribute, other: Attribute) -> Self {
+        Self::new(attribute, Relation::Equal, Some(other))
+    }
+
+    /// `attribute >= other`
+    pub fn at_least(attribute: Attribute, other: Attribute) -> Self {
+        Self::new(attribute, Relation::GreaterOrEqual, Some(other))
+    }
+
+    /// `attribute <= other`
+    pub fn at_most(attribute: Attribute, other: Attribute) -> Self {
+        Self::new(attribute, Relation::LessOrEqual, Some(other))
+    }
+
+    /// `attribute = constant`
+    pub fn fixed(attribute: Attribute, constant: f32) -> Self {
+        Self::new(attribute, Relation::Equal, None).plus(constant)
+    }
+
+    fn new(attribute: Attribute, relation: Relation, other: Option<Attribute>) -> Self {
+        Self {
+            attribute,
+            relation,
+            other,
+            multiplier: 1.0,
+            constant: 0.0,
+            strength: Strength::Required,
+        }
+    }
+
+    /// Adds the given constant to the other side of the constraint
+    pub fn plus(self, constant: f32) -> Self {
+        Self {
+            constant: self.constant + constant,
+            ..self
+        }
+    }
+
+    /// Multiplies the other attribute by the given multiplier
+    pub fn times(self, multiplier: f32) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    pub fn with_strength(self, strength: Strength) -> Self {
+        Self { strength, ..self }
+    }
+}
+
+/// An edge or size of either a child of a constraint layout, given by its index, or of the content
+/// box of the parent, where positions are from the top-left of that content box
+#[derive(Clone, Copy, PartialEq)]
+pub struct Attribute {
+    pub(crate) item: Item,
+    pub(crate) edge: Edge,
+}
+
+impl Attribute {
+    pub fn child(index: usize, edge: Edge) -> Self {
+        Self {
+            item: Item::Child(index),
+            edge,
+        }
+    }
+
+    pub fn parent(edge: Edge) -> Self {
+        Self {
+            item: Item::Parent,
+            edge,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Item {
+    Parent,
+    Child(usize),
+}
+
+/// The edges and sizes of a box that constraints are given between. When laid out from right to left,
+/// the whole layout is mirrored, so `Left` and `Right` are taken to mean the start and end
+#[derive(Clone, Copy, PartialEq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CentreX,
+    CentreY,
+    Width,
+    Height,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Relation {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+/// How strongly a constraint is kept when it conflicts with others, where any number of weaker
+/// constraints give way to a stronger one
+#[derive(Clone, Copy, PartialEq)]
+pub enum Strength {
+    /// Must be kept, or is otherwise left out entirely
+    Required,
+    Strong,
+    /// Stronger than the size each child asks for, which is kept at this strength otherwise
+    Medium,
+    /// Weaker than the size each child asks for, but stronger than where it would sit otherwise
+    Weak,
+}
+
+/// Determines whether the content of an element runs from left to right or from right to left.
+/// Right-to-left mirrors the whole layout of the element: rows flow from the right, children are
+/// aligned to the right, and the left padding, margin and anchors are taken to mean the start, which
+/// is on the right
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    pub fn is_rtl(&self) -> bool {
+        matches!(self, Direction::RightToLeft)
+    }
+}
+
+/// Determines whether flowed children are kept on a single line along the main axis, or may be
+/// wrapped onto further lines
+#[derive(Clone, PartialEq)]
+pub enum Wrap {
+    /// Children are kept on a single line, and may overflow the parent
+    NoWrap,
+    /// Children that would overflow the parent are placed on a new line, below the previous line
+    /// for a `Row` or to the right of it for a `Column`. Justification and alignment apply within
+    /// each line
+    Wrap,
+}
+
+/// Determines how flowed children are distributed along the main axis, i.e. horizontally for a `Row`
+/// and vertically for a `Column`, when they do not take up all of the available space
+#[derive(Clone, PartialEq)]
+pub enum Justify {
+    /// Children are packed towards the start of the parent
+    Start,
+    /// Children are packed around the centre of the parent
+    Centre,
+    /// Children are packed towards the end of the parent
+    End,
+    /// The first and last children sit at the edges of the parent, with the free space shared evenly
+    /// between them
+    SpaceBetween,
+    /// Each child has an equal amount of space either side of it, so the edges get half as much space
+    /// as is found between children
+    SpaceAround,
+    /// The free space is shared evenly between the children and the edges of the parent
+    SpaceEvenly,
+}
+
+impl Justify {
+    /// Given the free space along the main axis, and the number of children sharing it, returns the
+    /// offset of the first child and the additional spacing placed after each child.
+    ///
+    /// When there is no free space, `SpaceBetween` falls back to `Start` and the other spaced
+    /// variants to `Centre`
+    pub(crate) fn distribute(&self, free_space: f32, count: f32) -> (f32, f32) {
+        if count == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        match self {
+            Justify::Start => (0.0, 0.0),
+            Justify::Centre => (free_space / 2.0, 0.0),
+            Justify::End => (free_space, 0.0),
+            _ if free_space <= 0.0 => match self {
+                Justify::SpaceBetween => (0.0, 0.0),
+                _ => (free_space / 2.0, 0.0),
+            },
+            Justify::SpaceBetween if count == 1.0 => (0.0, 0.0),
+            Justify::SpaceBetween => (0.0, free_space / (count - 1.0)),
+            Justify::SpaceAround => (free_space / count / 2.0, free_space / count),
+            Justify::SpaceEvenly => (free_space / (count + 1.0), free_space / (count + 1.0)),
+        }
+    }
+}
+
+/// Determines where flowed children are placed along the cross axis, i.e. vertically for a `Row` and
+/// horizontally for a `Column`
+#[derive(Clone, PartialEq)]
+pub enum Align {
+    /// Children sit against the start edge of the parent
+    Start,
+    /// Children are centred within the parent
+    Centre,
+    /// Children sit against the end edge of the parent
+    End,
+    /// Children are sized to fill the parent, regardless of the size they asked for
+    Stretch,
+}
+
+impl Align {
+    /// Returns the offset from the start edge of the parent at which a child of the given size should
+    /// be placed, where the margins are those of the child on the start and end edges respectively
+    pub(crate) fn offset(
+        &self,
+        parent_size: f32,
+        child_size: f32,
+        margin_start: f32,
+        margin_end: f32,
+    ) -> f32 {
+        match self {
+            Align::Start | Align::Stretch => margin_start,
+            Align::Centre => {
+                margin_start + (parent_size - child_size - margin_start - margin_end) / 2.0
+            }
+            Align::End => parent_size - child_size - margin_end,
+        }
+    }
+}
+
+/// Determines how children that overflow the box of an element are treated
+#[derive(Clone, PartialEq)]
+pub enum Overflow {
+    /// Children are painted in full, even outside of the element
+    Visible,
+    /// Children are clipped to the box of the element
+    Hidden,
+    /// Children are clipped to the box of the element, and may be scrolled through, up to the extent of
+    /// their content
+    Scroll,
+}
+
+impl Overflow {
+    /// Whether children are clipped to the box of the element
+    pub fn clips(&self) -> bool {
+        !matches!(self, Overflow::Visible)
+    }
 }
 
 /// Determines the rules by which this element must adhere to when screenspace position is computed
+#[derive(Clone)]
 pub enum Position {
     /// `Static` is the default flow of elements whereby siblings are placed next to each other, and children
     /// are placed within the parent
@@ -38,11 +645,33 @@ pub enum Position {
     /// with some offset
     Relative(Offset),
     /// `Absolute` effectively ignores the default flow and is treated as a new layer.
-    /// For this reason, an anchor point must be decided, from which this element can be offset
+    /// For this reason, an anchor point must be decided, from which this element can be offset. The
+    /// anchor is within the content box of the nearest positioned ancestor, or the layer root, which
+    /// is also what its size is relative to
     Absolute(Offset, Anchor),
+    /// `Sticky` uses the default `Static` flow of elements until scrolling would move it past the given
+    /// offset from the start of the nearest scroll container, or the layer root, at which point it is
+    /// pinned there. Only the axes the offset is given on are pinned, so the element scrolls along the
+    /// others as usual. It is never pinned outside of the content box of its parent, so is pushed along
+    /// once the end of its parent is scrolled past
+    Sticky(StickyOffset),
+}
+
+impl Position {
+    pub fn is_absolute(&self) -> bool {
+        matches!(self, Position::Absolute(_, _))
+    }
+
+    /// Whether this element is the containing block of its absolutely positioned descendants, as
+    /// any element that is not `Static` is
+    pub fn is_positioned(&self) -> bool {
+        !matches!(self, Position::Static)
+    }
 }
 
+#[derive(Clone)]
 pub enum Anchor {
+    Centre,
     Top,
     TopRight,
     Right,
@@ -53,41 +682,307 @@ pub enum Anchor {
     TopLeft,
 }
 
+#[derive(Clone)]
 pub struct Offset {
-    pub x: f32,
-    pub y: f32,
+    pub x: Length,
+    pub y: Length,
 }
 
 impl Offset {
-    pub fn new(x: f32, y: f32) -> Self {
-        Self { x, y }
+    /// An offset in pixels, or any other unit, e.g. `Offset::new(0.0, Length::Em(1.5))`
+    pub fn new(x: impl Into<Length>, y: impl Into<Length>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// An offset given as a percentage (0-100) of the width and height of the box the element is
+    /// positioned within
+    pub fn percent(x: f32, y: f32) -> Self {
+        Self {
+            x: Length::Percent(x),
+            y: Length::Percent(y),
+        }
     }
 
     pub const fn none() -> Self {
-        Self { x: 0.0, y: 0.0 }
+        Self {
+            x: Length::Pixels(0.0),
+            y: Length::Pixels(0.0),
+        }
+    }
+
+    /// Resolves the offset in pixels, against the width and height of the box the element is
+    /// positioned within, and the units of the element
+    pub fn resolve(&self, width: f32, height: f32, units: &Units) -> (f32, f32) {
+        (self.x.resolve(width, units), self.y.resolve(height, units))
+    }
+}
+
+/// The offset a sticky element is pinned at from the start of its scroll container, on each axis it
+/// is pinned on, where an axis left as `None` is scrolled as usual
+#[derive(Clone, Default)]
+pub struct StickyOffset {
+    pub x: Option<Length>,
+    pub y: Option<Length>,
+}
+
+impl StickyOffset {
+    /// Pins the element on both axes, e.g. `StickyOffset::new(0.0, Length::Em(1.5))`
+    pub fn new(x: impl Into<Length>, y: impl Into<Length>) -> Self {
+        Self {
+            x: Some(x.into()),
+            y: Some(y.into()),
+        }
+    }
+
+    /// Pins the element at the given offset from the top only, such as for the header of a table
+    pub fn top(y: impl Into<Length>) -> Self {
+        Self {
+            x: None,
+            y: Some(y.into()),
+        }
+    }
+
+    /// Pins the element at the given offset from the start of the row only, which is the right when
+    /// laid out from right to left
+    pub fn start(x: impl Into<Length>) -> Self {
+        Self {
+            x: Some(x.into()),
+            y: None,
+        }
+    }
+}
+
+/// A distance in any of the units that layout and style may be given in. Relative units are resolved
+/// to pixels during layout, so they follow changes to font sizes and the size of the window
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Length {
+    Pixels(f32),
+    /// `Percent` is given in the range 0-100, of whatever the length is relative to
+    Percent(f32),
+    /// `Em` is a multiple of the font size of the element
+    Em(f32),
+    /// `Rem` is a multiple of the root font size, which follows the font size preferred by the user
+    Rem(f32),
+    /// `Vw` is given in the range 0-100, of the width of the window
+    Vw(f32),
+    /// `Vh` is given in the range 0-100, of the height of the window
+    Vh(f32),
+}
+
+impl Length {
+    /// Resolves the length in pixels, where percentages are of the given reference
+    pub fn resolve(&self, reference: f32, units: &Units) -> f32 {
+        match self {
+            Length::Pixels(pixels) => *pixels,
+            Length::Percent(percent) => reference * percent / 100.0,
+            Length::Em(em) => units.font_size * em,
+            Length::Rem(rem) => units.root_font_size * rem,
+            Length::Vw(vw) => units.viewport_width * vw / 100.0,
+            Length::Vh(vh) => units.viewport_height * vh / 100.0,
+        }
+    }
+}
+
+impl From<f32> for Length {
+    fn from(pixels: f32) -> Self {
+        Length::Pixels(pixels)
     }
 }
 
+/// The sizes that relative lengths are resolved against
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Units {
+    /// the font size of the element, for `Em`
+    pub font_size: f32,
+    /// the root font size, for `Rem`
+    pub root_font_size: f32,
+    /// the size of the window, for `Vw` and `Vh`
+    pub viewport_width: f32,
+    pub viewport_height: f32,
+}
+
 #[derive(Default, Clone)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
 }
 
+/// An axis-aligned box in screenspace, given by its top-left position
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// A box large enough to contain anything, for when nothing is clipped
+    pub const fn unbounded() -> Self {
+        Self {
+            x: f32::MIN / 2.0,
+            y: f32::MIN / 2.0,
+            width: f32::MAX,
+            height: f32::MAX,
+        }
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Returns the horizontal position of a box of the given width, such that it is placed within
+    /// this box as if it were mirrored left to right
+    pub fn mirror_x(&self, x: f32, width: f32) -> f32 {
+        2.0 * self.x + self.width - x - width
+    }
+
+    /// Returns this box with its edges rounded to the nearest physical pixel, given the number of
+    /// physical pixels to each logical pixel, so that edges are drawn sharp
+    pub fn snapped(&self, scale_factor: f32) -> Rect {
+        // in double precision, so that an unbounded box stays within range
+        let scale_factor = scale_factor as f64;
+        let snap = |value: f32| ((value as f64 * scale_factor).round() / scale_factor) as f32;
+        // a box that has any size keeps at least one physical pixel of it, rather than vanishing
+        let pixel = (1.0 / scale_factor) as f32;
+        let size = |start: f32, size: f32| match size > 0.0 {
+            true => f32::max(snap(start + size) - snap(start), pixel),
+            false => snap(start + size) - snap(start),
+        };
+        Rect {
+            x: snap(self.x),
+            y: snap(self.y),
+            width: size(self.x, self.width),
+            height: size(self.y, self.height),
+        }
+    }
+
+    /// Returns the overlap of both boxes, which has no size if they do not overlap
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect {
+            x,
+            y,
+            width: f32::max((self.x + self.width).min(other.x + other.width) - x, 0.0),
+            height: f32::max((self.y + self.height).min(other.y + other.height) - y, 0.0),
+        }
+    }
+}
+
 pub struct Dimensions {
     pub width: f32,
     pub height: f32,
 }
 
-#[derive(Clone)]
+impl Dimensions {
+    /// Converts a size in physical pixels, such as that of a window, to logical pixels, given the
+    /// number of physical pixels to each logical pixel
+    pub fn from_physical(width: u32, height: u32, scale_factor: f32) -> Self {
+        Self {
+            width: width as f32 / scale_factor,
+            height: height as f32 / scale_factor,
+        }
+    }
+}
+
+/// Space between the box of an element and its content, in pixels or any other unit. As padding is
+/// needed before the parent of the element is sized, a percentage is of the width of the window
+#[derive(Clone, PartialEq)]
 pub struct Padding {
+    pub top: Length,
+    pub right: Length,
+    pub bottom: Length,
+    pub left: Length,
+}
+
+impl Padding {
+    pub fn new(
+        top: impl Into<Length>,
+        right: impl Into<Length>,
+        bottom: impl Into<Length>,
+        left: impl Into<Length>,
+    ) -> Self {
+        Self {
+            top: top.into(),
+            right: right.into(),
+            bottom: bottom.into(),
+            left: left.into(),
+        }
+    }
+
+    pub fn uniform(padding: impl Into<Length>) -> Self {
+        let padding = padding.into();
+        Self::new(padding, padding, padding, padding)
+    }
+
+    pub const fn none() -> Self {
+        Self {
+            top: Length::Pixels(0.0),
+            right: Length::Pixels(0.0),
+            bottom: Length::Pixels(0.0),
+            left: Length::Pixels(0.0),
+        }
+    }
+
+    /// Resolves the padding in pixels against the given units
+    pub fn resolve(&self, units: &Units) -> ComputedPadding {
+        let resolve = |length: &Length| length.resolve(units.viewport_width, units);
+        ComputedPadding {
+            top: resolve(&self.top),
+            right: resolve(&self.right),
+            bottom: resolve(&self.bottom),
+            left: resolve(&self.left),
+        }
+    }
+}
+
+/// Padding resolved in pixels
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ComputedPadding {
     pub top: f32,
     pub right: f32,
     pub bottom: f32,
     pub left: f32,
 }
 
-impl Padding {
+impl ComputedPadding {
+    /// Sum of the left and right padding
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// Sum of the top and bottom padding
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Margin {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Margin {
+    pub fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    pub fn uniform(margin: f32) -> Self {
+        Self::new(margin, margin, margin, margin)
+    }
+
     pub const fn none() -> Self {
         Self {
             top: 0.0,
@@ -96,4 +991,79 @@ impl Padding {
             left: 0.0,
         }
     }
+
+    /// Sum of the left and right margin
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// Sum of the top and bottom margin
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Asserts that both boxes are the same, but for rounding
+    fn assert_close(actual: Rect, expected: Rect) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            close(actual.x, expected.x)
+                && close(actual.y, expected.y)
+                && close(actual.width, expected.width)
+                && close(actual.height, expected.height),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn snap_to_physical_pixels() {
+        // at a scale factor of 1.25, physical pixels are 0.8 logical pixels apart
+        assert_close(
+            rect(10.3, 0.5, 20.0, 10.1).snapped(1.25),
+            rect(10.4, 0.8, 20.0, 9.6),
+        );
+        // both edges are snapped, so the width follows the snapped right edge
+        assert_close(
+            rect(0.4, 0.0, 1.0, 1.0).snapped(1.5),
+            rect(2.0 / 3.0, 0.0, 2.0 / 3.0, 2.0 / 3.0 * 2.0),
+        );
+        assert_close(
+            rect(-10.3, -0.2, 5.0, 5.0).snapped(2.0),
+            rect(-10.5, 0.0, 5.0, 5.0),
+        );
+        // a thin box keeps a single physical pixel, while an empty one stays empty
+        assert_close(
+            rect(3.1, 3.1, 0.2, 0.2).snapped(1.0),
+            rect(3.0, 3.0, 1.0, 1.0),
+        );
+        assert_close(
+            rect(3.4, 3.4, 0.2, 0.0).snapped(2.0),
+            rect(3.5, 3.5, 0.5, 0.0),
+        );
+        // an unbounded box stays unbounded
+        assert!(Rect::unbounded().snapped(1.5).contains(1e30, -1e30));
+    }
+
+    #[test]
+    fn physical_to_logical() {
+        let logical = Dimensions::from_physical(1600, 1200, 2.0);
+        assert_eq!((logical.width, logical.height), (800.0, 600.0));
+        let logical = Dimensions::from_physical(1000, 750, 1.25);
+        assert_eq!((logical.width, logical.height), (800.0, 600.0));
+        let logical = Dimensions::from_physical(1280, 720, 1.0);
+        assert_eq!((logical.width, logical.height), (1280.0, 720.0));
+    }
 }
@@ -1,9 +1,14 @@
 pub mod application;
 pub mod component;
+pub mod constraints;
+pub mod diagnostics;
 pub mod event;
+pub mod grid;
 pub mod layer;
 pub mod layout;
 pub mod renderer;
+pub mod rules;
+pub mod stacking;
 pub mod style;
 pub mod tree;
 pub mod window;
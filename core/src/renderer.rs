@@ -3,7 +3,9 @@ pub trait Renderer {
     fn from_loader_function<F>(loader_function: F) -> Self
     where
         F: FnMut(&str) -> *const std::os::raw::c_void;
-    fn update_viewport(&self, width: u32, height: u32);
+    /// Sets the size of the area drawn to in physical pixels, where quads are given in logical pixels
+    /// of which there are `scale_factor` physical pixels to each
+    fn update_viewport(&self, width: u32, height: u32, scale_factor: f32);
     fn draw(&mut self, quad: Quad);
     fn present(&mut self);
 }
@@ -13,11 +15,15 @@ pub trait Renderer {
 pub struct Quad {
     pub position: [f32; 2],
     pub dimensions: [f32; 2],
-    pub colour: [f32; 3],
+    /// the background and border colours, each with its own alpha
+    pub colour: [f32; 4],
+    /// the opacity of the whole quad, which the alpha of both colours is multiplied by
     pub opacity: f32,
-    pub border_colour: [f32; 3],
+    pub border_colour: [f32; 4],
     pub border_radius: [f32; 4],
     pub border_weight: f32,
+    /// the screenspace box, as x, y, width and height, outside of which nothing of this quad is drawn
+    pub clip: [f32; 4],
 }
 
 unsafe impl bytemuck::Zeroable for Quad {}
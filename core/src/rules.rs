@@ -0,0 +1,802 @@
+use std::{error::Error, fmt, iter::Peekable, str::Chars, str::FromStr};
+
+use crate::{
+    component::Component,
+    layout::{Align, Justify, Layout, Length, Margin, Orientation, Overflow, Padding, Size, Wrap},
+    style::{BorderRadius, Colour, Style},
+};
+
+/// Style rules written in a CSS-like text format, which are applied to every component they match
+/// as part of the cascade, e.g.
+///
+/// ```text
+/// /* comments are ignored */
+/// button, .action {
+///     background-colour: #3478f6;
+///     border-radius: 4px;
+///     padding: 0.5rem 1rem;
+/// }
+///
+/// button.primary#submit { font-weight: 700; }
+/// ```
+///
+/// A selector matches components by type, which is their tag, by class and by id, where a selector
+/// given all three must match on each, and `*` matches any component. Where rules set the same
+/// property, that of the most specific selector is applied, i.e. the one with the most ids, then
+/// classes, then types, and otherwise that of the later rule.
+///
+/// Properties set on the component itself take precedence over rules. As layout has no notion of
+/// being unset, layout properties given by rules only apply where the component leaves them at their
+/// initial values, and the layout of the component itself is never changed
+#[derive(Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    selectors: Vec<Selector>,
+    declarations: Vec<Declaration>,
+}
+
+/// The number of ids, classes and types in a selector, which are compared in that order
+type Specificity = (usize, usize, usize);
+
+/// A compound selector, which matches components that have the tag, every class and the id given
+#[derive(Default)]
+struct Selector {
+    tag: Option<String>,
+    classes: Vec<String>,
+    id: Option<String>,
+}
+
+impl Selector {
+    fn matches(&self, component: &Component) -> bool {
+        (self.tag.is_none() || component.tag == self.tag)
+            && (self.id.is_none() || component.id == self.id)
+            && self
+                .classes
+                .iter()
+                .all(|class| component.classes.contains(class))
+    }
+
+    fn specificity(&self) -> Specificity {
+        (
+            self.id.is_some() as usize,
+            self.classes.len(),
+            self.tag.is_some() as usize,
+        )
+    }
+}
+
+impl Rules {
+    pub fn parse(text: &str) -> Result<Self, ParseRulesError> {
+        Parser::new(text).parse_rules()
+    }
+
+    /// Returns the indices of the rules that match the given component, in the order they are applied
+    pub(crate) fn matching(&self, component: &Component) -> Vec<usize> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        let mut matched: Vec<(Specificity, usize)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rule)| {
+                rule.selectors
+                    .iter()
+                    .filter(|selector| selector.matches(component))
+                    .map(Selector::specificity)
+                    .max()
+                    .map(|specificity| (specificity, i))
+            })
+            .collect();
+        matched.sort();
+        matched.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// Returns the style overrides given by the rules of the given indices, where later rules take
+    /// precedence
+    pub(crate) fn style(&self, matched: &[usize]) -> Style {
+        let mut style = Style::default();
+        for declaration in self.declarations(matched) {
+            declaration.apply_style(&mut style);
+        }
+        style
+    }
+
+    /// Writes the layout properties given by the rules of the given indices over the given layout,
+    /// where the authored layout of the component leaves them at their initial values
+    pub(crate) fn apply_layout(&self, matched: &[usize], authored: &Layout, layout: &mut Layout) {
+        let initial = Layout::default();
+        for declaration in self.declarations(matched) {
+            declaration.apply_layout(layout, authored, &initial);
+        }
+    }
+
+    fn declarations<'a>(&'a self, matched: &'a [usize]) -> impl Iterator<Item = &'a Declaration> {
+        matched
+            .iter()
+            .flat_map(|i| self.rules[*i].declarations.iter())
+    }
+}
+
+/// The rules that matched a component, along with the tag, classes and id they were matched against,
+/// so that they are only matched again once those change
+pub(crate) struct MatchedRules {
+    selected: Selector,
+    pub(crate) indices: Vec<usize>,
+    /// the style overrides given by the matched rules
+    pub(crate) style: Style,
+}
+
+impl MatchedRules {
+    pub(crate) fn new(rules: &Rules, component: &Component) -> Self {
+        let indices = rules.matching(component);
+        Self {
+            selected: Selector {
+                tag: component.tag.clone(),
+                classes: component.classes.clone(),
+                id: component.id.clone(),
+            },
+            style: rules.style(&indices),
+            indices,
+        }
+    }
+
+    /// Returns whether the rules were matched against the current tag, classes and id of the component
+    pub(crate) fn is_current(&self, component: &Component) -> bool {
+        self.selected.tag == component.tag
+            && self.selected.id == component.id
+            && self.selected.classes == component.classes
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ParseRulesError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+/// A single property of a rule, as it is applied to either the style or layout of a component
+enum Declaration {
+    Rotate(f32),
+    Opacity(f32),
+    BackgroundColour(Colour),
+    FontSize(f32),
+    FontWeight(f32),
+    FontColour(Colour),
+    BorderWeight(Length),
+    BorderColour(Colour),
+    BorderRadius(BorderRadius),
+
+    Orientation(Orientation),
+    Width(Size),
+    Height(Size),
+    MinWidth(f32),
+    MaxWidth(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    Padding(Padding),
+    Margin(Margin),
+    Gap(f32),
+    LineGap(f32),
+    Justify(Justify),
+    Align(Align),
+    Wrap(Wrap),
+    Overflow(Overflow),
+    ZIndex(i32),
+}
+
+impl Declaration {
+    /// Parses the value of the property of the given name, returning `None` when the property is not
+    /// known, and otherwise an error describing the value expected when it is invalid
+    fn parse(name: &str, value: &str) -> Option<Result<Self, &'static str>> {
+        let declaration = match name {
+            "rotate" => parse_number(value).map(Declaration::Rotate),
+            "opacity" => parse_number(value).map(Declaration::Opacity),
+            "background-colour" => parse_colour(value).map(Declaration::BackgroundColour),
+            "font-size" => parse_pixels(value).map(Declaration::FontSize),
+            "font-weight" => parse_number(value).map(Declaration::FontWeight),
+            "font-colour" => parse_colour(value).map(Declaration::FontColour),
+            "border-weight" => parse_length(value).map(Declaration::BorderWeight),
+            "border-colour" => parse_colour(value).map(Declaration::BorderColour),
+            "border-radius" => parse_border_radius(value).map(Declaration::BorderRadius),
+
+            "orientation" => match value {
+                "row" => Ok(Orientation::Row),
+                "column" => Ok(Orientation::Column),
+                "stack" => Ok(Orientation::Stack),
+                _ => Err("`row`, `column` or `stack`"),
+            }
+            .map(Declaration::Orientation),
+            "width" => parse_size(value).map(Declaration::Width),
+            "height" => parse_size(value).map(Declaration::Height),
+            "min-width" => parse_pixels(value).map(Declaration::MinWidth),
+            "max-width" => parse_pixels(value).map(Declaration::MaxWidth),
+            "min-height" => parse_pixels(value).map(Declaration::MinHeight),
+            "max-height" => parse_pixels(value).map(Declaration::MaxHeight),
+            "padding" => parse_sides(value, parse_length)
+                .map(|[top, right, bottom, left]| Padding::new(top, right, bottom, left))
+                .map(Declaration::Padding),
+            "margin" => parse_sides(value, parse_pixels)
+                .map(|[top, right, bottom, left]| Margin::new(top, right, bottom, left))
+                .map(Declaration::Margin),
+            "gap" => parse_pixels(value).map(Declaration::Gap),
+            "line-gap" => parse_pixels(value).map(Declaration::LineGap),
+            "justify" => match value {
+                "start" => Ok(Justify::Start),
+                "centre" => Ok(Justify::Centre),
+                "end" => Ok(Justify::End),
+                "space-between" => Ok(Justify::SpaceBetween),
+                "space-around" => Ok(Justify::SpaceAround),
+                "space-evenly" => Ok(Justify::SpaceEvenly),
+                _ => Err(
+                    "`start`, `centre`, `end`, `space-between`, `space-around` or `space-evenly`",
+                ),
+            }
+            .map(Declaration::Justify),
+            "align" => match value {
+                "start" => Ok(Align::Start),
+                "centre" => Ok(Align::Centre),
+                "end" => Ok(Align::End),
+                "stretch" => Ok(Align::Stretch),
+                _ => Err("`start`, `centre`, `end` or `stretch`"),
+            }
+            .map(Declaration::Align),
+            "wrap" => match value {
+                "no-wrap" => Ok(Wrap::NoWrap),
+                "wrap" => Ok(Wrap::Wrap),
+                _ => Err("`no-wrap` or `wrap`"),
+            }
+            .map(Declaration::Wrap),
+            "overflow" => match value {
+                "visible" => Ok(Overflow::Visible),
+                "hidden" => Ok(Overflow::Hidden),
+                "scroll" => Ok(Overflow::Scroll),
+                _ => Err("`visible`, `hidden` or `scroll`"),
+            }
+            .map(Declaration::Overflow),
+            "z-index" => value
+                .parse()
+                .map_err(|_| "an integer")
+                .map(Declaration::ZIndex),
+            _ => return None,
+        };
+        Some(declaration)
+    }
+
+    fn apply_style(&self, style: &mut Style) {
+        match self {
+            Declaration::Rotate(rotate) => style.rotate = Some(*rotate),
+            Declaration::Opacity(opacity) => style.opacity = Some(*opacity),
+            Declaration::BackgroundColour(colour) => style.background_colour = Some(*colour),
+            Declaration::FontSize(size) => style.font_size = Some(*size),
+            Declaration::FontWeight(weight) => style.font_weight = Some(*weight),
+            Declaration::FontColour(colour) => style.font_colour = Some(*colour),
+            Declaration::BorderWeight(weight) => style.border_weight = Some(*weight),
+            Declaration::BorderColour(colour) => style.border_colour = Some(*colour),
+            Declaration::BorderRadius(radius) => style.border_radius = Some(*radius),
+            _ => {}
+        }
+    }
+
+    /// Applies the declaration to the given layout, unless the authored layout changed the property
+    /// from its initial value
+    fn apply_layout(&self, layout: &mut Layout, authored: &Layout, initial: &Layout) {
+        match self {
+            Declaration::Orientation(orientation)
+                if authored.orientation == initial.orientation =>
+            {
+                layout.orientation = orientation.clone()
+            }
+            Declaration::Width(size) if authored.width == initial.width => {
+                layout.width = size.clone()
+            }
+            Declaration::Height(size) if authored.height == initial.height => {
+                layout.height = size.clone()
+            }
+            Declaration::MinWidth(size) if authored.min_width == initial.min_width => {
+                layout.min_width = *size
+            }
+            Declaration::MaxWidth(size) if authored.max_width == initial.max_width => {
+                layout.max_width = *size
+            }
+            Declaration::MinHeight(size) if authored.min_height == initial.min_height => {
+                layout.min_height = *size
+            }
+            Declaration::MaxHeight(size) if authored.max_height == initial.max_height => {
+                layout.max_height = *size
+            }
+            Declaration::Padding(padding) if authored.padding == initial.padding => {
+                layout.padding = padding.clone()
+            }
+            Declaration::Margin(margin) if authored.margin == initial.margin => {
+                layout.margin = margin.clone()
+            }
+            Declaration::Gap(gap) if authored.gap == initial.gap => layout.gap = *gap,
+            Declaration::LineGap(gap) if authored.line_gap == initial.line_gap => {
+                layout.line_gap = *gap
+            }
+            Declaration::Justify(justify) if authored.justify == initial.justify => {
+                layout.justify = justify.clone()
+            }
+            Declaration::Align(align) if authored.align == initial.align => {
+                layout.align = align.clone()
+            }
+            Declaration::Wrap(wrap) if authored.wrap == initial.wrap => layout.wrap = wrap.clone(),
+            Declaration::Overflow(overflow) if authored.overflow == initial.overflow => {
+                layout.overflow = overflow.clone()
+            }
+            Declaration::ZIndex(z_index) if authored.z_index == initial.z_index => {
+                layout.z_index = Some(*z_index)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Result<f32, &'static str> {
+    value.parse().map_err(|_| "a number")
+}
+
+fn parse_colour(value: &str) -> Result<Colour, &'static str> {
+    value
+        .parse()
+        .map_err(|_| "a hex colour, or an `rgb`, `rgba`, `hsl` or `hsla` colour")
+}
+
+/// Parses a length in pixels, which may be given with or without `px`
+fn parse_pixels(value: &str) -> Result<f32, &'static str> {
+    parse_number(value.strip_suffix("px").unwrap_or(value)).map_err(|_| "a length in pixels")
+}
+
+/// Parses a length in any unit, where one given without a unit is in pixels
+fn parse_length(value: &str) -> Result<Length, &'static str> {
+    let number = |suffix: &str| value.strip_suffix(suffix)?.parse().ok();
+    // `rem` is checked before `em`, which it ends with
+    let length = if let Some(percent) = number("%") {
+        Length::Percent(percent)
+    } else if let Some(rem) = number("rem") {
+        Length::Rem(rem)
+    } else if let Some(em) = number("em") {
+        Length::Em(em)
+    } else if let Some(vw) = number("vw") {
+        Length::Vw(vw)
+    } else if let Some(vh) = number("vh") {
+        Length::Vh(vh)
+    } else {
+        parse_pixels(value)
+            .map(Length::Pixels)
+            .map_err(|_| "a length, in `px`, `%`, `em`, `rem`, `vw` or `vh`")?
+    };
+    Ok(length)
+}
+
+fn parse_size(value: &str) -> Result<Size, &'static str> {
+    match value {
+        "fill" => return Ok(Size::Fill),
+        "fit-content" => return Ok(Size::FitContent),
+        _ => {}
+    }
+    if let Some(weight) = value.strip_suffix("fr") {
+        return weight.parse().map(Size::Weighted).map_err(|_| "a weight");
+    }
+
+    parse_length(value)
+        .map(|length| match length {
+            Length::Pixels(pixels) => Size::Constant(pixels),
+            Length::Percent(percent) => Size::Percent(percent),
+            length => Size::Length(length),
+        })
+        .map_err(|_| "`fill`, `fit-content`, a weight in `fr`, or a length")
+}
+
+/// Parses one to four values for the sides of a box, in the order top, right, bottom and left, where
+/// missing sides are the same as the opposite side, or the top when only one is given
+fn parse_sides<T: Copy>(
+    value: &str,
+    parse: fn(&str) -> Result<T, &'static str>,
+) -> Result<[T; 4], &'static str> {
+    let values = value
+        .split_whitespace()
+        .map(parse)
+        .collect::<Result<Vec<T>, _>>()?;
+    match values[..] {
+        [all] => Ok([all; 4]),
+        [vertical, horizontal] => Ok([vertical, horizontal, vertical, horizontal]),
+        [top, horizontal, bottom] => Ok([top, horizontal, bottom, horizontal]),
+        [top, right, bottom, left] => Ok([top, right, bottom, left]),
+        _ => Err("one to four values"),
+    }
+}
+
+/// Parses one or four radii, in the order top-left, top-right, bottom-right and bottom-left
+fn parse_border_radius(value: &str) -> Result<BorderRadius, &'static str> {
+    let radii = value
+        .split_whitespace()
+        .map(parse_pixels)
+        .collect::<Result<Vec<f32>, _>>()?;
+    match radii[..] {
+        [all] => Ok(BorderRadius::new(all, all, all, all)),
+        [top_left, top_right, bottom_right, bottom_left] => Ok(BorderRadius::new(
+            top_right,
+            bottom_right,
+            bottom_left,
+            top_left,
+        )),
+        _ => Err("one or four radii"),
+    }
+}
+
+/// Why text couldn't be parsed as `Rules`, along with the line and column, both counting from one, at
+/// which the problem was found
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseRulesError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseRulesErrorKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParseRulesErrorKind {
+    /// The text ended within a rule or comment
+    UnexpectedEnd,
+    /// A character was found where another was expected
+    Expected {
+        expected: char,
+        found: char,
+    },
+    /// A selector is empty, or has something other than a type, classes and an id
+    InvalidSelector,
+    UnknownProperty(String),
+    /// The value of the property is not one it can take, which is described by `expected`
+    InvalidValue {
+        property: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ParseRulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseRulesErrorKind::UnexpectedEnd => write!(f, "unexpected end of rules"),
+            ParseRulesErrorKind::Expected { expected, found } => {
+                write!(f, "expected `{expected}`, found `{found}`")
+            }
+            ParseRulesErrorKind::InvalidSelector => write!(f, "invalid selector"),
+            ParseRulesErrorKind::UnknownProperty(property) => {
+                write!(f, "unknown property `{property}`")
+            }
+            ParseRulesErrorKind::InvalidValue { property, expected } => {
+                write!(f, "`{property}` expects {expected}")
+            }
+        }
+    }
+}
+
+impl Error for ParseRulesError {}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn error(&self, kind: ParseRulesErrorKind) -> ParseRulesError {
+        ParseRulesError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            _ => self.column += 1,
+        }
+        Some(c)
+    }
+
+    /// Skips any whitespace and comments
+    fn skip_whitespace(&mut self) -> Result<(), ParseRulesError> {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.next();
+                }
+                Some('/') => {
+                    let (line, column) = (self.line, self.column);
+                    self.next();
+                    if self.next() != Some('*') {
+                        return Err(ParseRulesError {
+                            line,
+                            column,
+                            kind: ParseRulesErrorKind::InvalidSelector,
+                        });
+                    }
+                    let mut previous = None;
+                    loop {
+                        match self.next() {
+                            Some('/') if previous == Some('*') => break,
+                            Some(c) => previous = Some(c),
+                            None => return Err(self.error(ParseRulesErrorKind::UnexpectedEnd)),
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseRulesError> {
+        match self.chars.peek().copied() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(found) => Err(self.error(ParseRulesErrorKind::Expected { expected, found })),
+            None => Err(self.error(ParseRulesErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    /// Reads the name of a type, class, id or property, which may be empty
+    fn identifier(&mut self) -> String {
+        let mut identifier = String::new();
+        while let Some(c) = self.chars.peek() {
+            if !(c.is_alphanumeric() || *c == '-' || *c == '_') {
+                break;
+            }
+            identifier.push(*c);
+            self.next();
+        }
+        identifier
+    }
+
+    fn parse_rules(&mut self) -> Result<Rules, ParseRulesError> {
+        let mut rules = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.chars.peek().is_none() {
+                return Ok(Rules { rules });
+            }
+
+            let selectors = self.parse_selectors()?;
+            self.expect('{')?;
+            let declarations = self.parse_declarations()?;
+            self.expect('}')?;
+            rules.push(Rule {
+                selectors,
+                declarations,
+            });
+        }
+    }
+
+    /// Parses a comma separated list of selectors, up to the `{` that starts the declarations
+    fn parse_selectors(&mut self) -> Result<Vec<Selector>, ParseRulesError> {
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            let invalid = self.error(ParseRulesErrorKind::InvalidSelector);
+            let mut selector = Selector::default();
+            let mut is_empty = true;
+
+            match self.chars.peek() {
+                Some('*') => {
+                    self.next();
+                    is_empty = false;
+                }
+                _ => {
+                    let tag = self.identifier();
+                    if !tag.is_empty() {
+                        selector.tag = Some(tag);
+                        is_empty = false;
+                    }
+                }
+            }
+            loop {
+                let (line, column) = (self.line, self.column);
+                let (is_class, name) = match self.chars.peek() {
+                    Some('.') => {
+                        self.next();
+                        (true, self.identifier())
+                    }
+                    Some('#') => {
+                        self.next();
+                        (false, self.identifier())
+                    }
+                    _ => break,
+                };
+                if name.is_empty() || (!is_class && selector.id.is_some()) {
+                    return Err(ParseRulesError {
+                        line,
+                        column,
+                        kind: ParseRulesErrorKind::InvalidSelector,
+                    });
+                }
+                match is_class {
+                    true => selector.classes.push(name),
+                    false => selector.id = Some(name),
+                }
+                is_empty = false;
+            }
+            if is_empty {
+                return Err(invalid);
+            }
+            selectors.push(selector);
+
+            self.skip_whitespace()?;
+            match self.chars.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('{') => return Ok(selectors),
+                Some(_) => return Err(self.error(ParseRulesErrorKind::InvalidSelector)),
+                None => return Err(self.error(ParseRulesErrorKind::UnexpectedEnd)),
+            }
+        }
+    }
+
+    /// Parses declarations up to the `}` that ends them, where each is a property and value separated
+    /// by `:`, and is ended by `;` unless it is the last
+    fn parse_declarations(&mut self) -> Result<Vec<Declaration>, ParseRulesError> {
+        let mut declarations = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if let Some('}') = self.chars.peek() {
+                return Ok(declarations);
+            }
+
+            let property_error = self.error(ParseRulesErrorKind::UnexpectedEnd);
+            let property = self.identifier();
+            self.skip_whitespace()?;
+            self.expect(':')?;
+            self.skip_whitespace()?;
+
+            let value_error = self.error(ParseRulesErrorKind::UnexpectedEnd);
+            let mut value = String::new();
+            while let Some(c) = self.chars.peek() {
+                if *c == ';' || *c == '}' {
+                    break;
+                }
+                value.push(*c);
+                self.next();
+            }
+            if self.chars.peek().is_none() {
+                return Err(self.error(ParseRulesErrorKind::UnexpectedEnd));
+            }
+            if let Some(';') = self.chars.peek() {
+                self.next();
+            }
+
+            match Declaration::parse(&property, value.trim()) {
+                Some(Ok(declaration)) => declarations.push(declaration),
+                Some(Err(expected)) => {
+                    return Err(ParseRulesError {
+                        kind: ParseRulesErrorKind::InvalidValue { property, expected },
+                        ..value_error
+                    })
+                }
+                None => {
+                    return Err(ParseRulesError {
+                        kind: ParseRulesErrorKind::UnknownProperty(property),
+                        ..property_error
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        let rules = Rules::parse(
+            "/* buttons */
+            button, .action.large { padding: 1em 2px 3px; border-radius: 1 2 3 4 }
+            *#ok{width:fill;height:2fr;}
+            ",
+        )
+        .unwrap();
+        assert_eq!(rules.rules.len(), 2);
+
+        let selectors = &rules.rules[0].selectors;
+        assert_eq!(selectors[0].specificity(), (0, 0, 1));
+        assert_eq!(selectors[1].classes, ["action", "large"]);
+        assert_eq!(selectors[1].specificity(), (0, 2, 0));
+        assert_eq!(rules.rules[1].selectors[0].specificity(), (1, 0, 0));
+
+        let style = rules.style(&[0]);
+        assert_eq!(
+            style.border_radius,
+            Some(BorderRadius::new(2.0, 3.0, 4.0, 1.0))
+        );
+        let authored = Component::new().layout;
+        let mut layout = authored.clone();
+        rules.apply_layout(&[0, 1], &authored, &mut layout);
+        assert_eq!(layout.padding.top, Length::Em(1.0));
+        assert_eq!(layout.padding.left, Length::Pixels(2.0));
+        assert_eq!(layout.padding.bottom, Length::Pixels(3.0));
+        assert!(matches!(layout.width, Size::Fill));
+        assert!(matches!(layout.height, Size::Weighted(w) if w == 2.0));
+    }
+
+    #[test]
+    fn report_errors() {
+        let error = |text: &str| {
+            let error = Rules::parse(text).err().unwrap();
+            (error.line, error.column, error.kind)
+        };
+
+        assert_eq!(
+            error("a {\n  colour: red;\n}"),
+            (
+                2,
+                3,
+                ParseRulesErrorKind::UnknownProperty("colour".to_string())
+            )
+        );
+        assert_eq!(
+            error("a { width: wide }"),
+            (
+                1,
+                12,
+                ParseRulesErrorKind::InvalidValue {
+                    property: "width".to_string(),
+                    expected: "`fill`, `fit-content`, a weight in `fr`, or a length",
+                }
+            )
+        );
+        assert_eq!(
+            error("a b { }"),
+            (1, 3, ParseRulesErrorKind::InvalidSelector)
+        );
+        assert_eq!(
+            error(".a, { }"),
+            (1, 5, ParseRulesErrorKind::InvalidSelector)
+        );
+        assert_eq!(
+            error("#a#b { }"),
+            (1, 3, ParseRulesErrorKind::InvalidSelector)
+        );
+        assert_eq!(
+            error("a { opacity 1 }"),
+            (
+                1,
+                13,
+                ParseRulesErrorKind::Expected {
+                    expected: ':',
+                    found: '1'
+                }
+            )
+        );
+        assert_eq!(
+            error("a { opacity: 1"),
+            (1, 15, ParseRulesErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error("/* a { }"),
+            (1, 9, ParseRulesErrorKind::UnexpectedEnd)
+        );
+    }
+}
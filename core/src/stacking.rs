@@ -0,0 +1,163 @@
+use crate::{component::Component, layout::Rect, tree::Node};
+
+/// A component as it is painted, along with the box it is clipped to by its ancestors
+pub(crate) struct Painted<'a> {
+    pub(crate) node: &'a Node<Component>,
+    pub(crate) clip: Rect,
+    /// the entry of the parent of this component, and the index of this component amongst its
+    /// children, which give the path to it from the root
+    parent: Option<usize>,
+    child_index: usize,
+}
+
+/// The order in which the components of a layer are painted, which is also the reverse of the order
+/// in which they are hit.
+///
+/// A component with a z-index forms a stacking context, which is painted as a whole at its z-index
+/// within the nearest stacking context around it, after those with a lower z-index and before those
+/// with a higher one. Those with the same z-index are painted in the order they are defined, where
+/// components without a z-index are at zero. A component without a z-index doesn't form a stacking
+/// context, so the z-index of anything within it is relative to the stacking context around it
+pub(crate) struct PaintOrder<'a> {
+    entries: Vec<Painted<'a>>,
+    order: Vec<usize>,
+}
+
+impl<'a> PaintOrder<'a> {
+    /// Orders the tree of the given root, which is always a stacking context
+    pub(crate) fn new(root: &'a Node<Component>) -> Self {
+        let mut paint_order = Self {
+            entries: Vec::new(),
+            order: Vec::new(),
+        };
+        paint_order.order = paint_order.stack(root, Rect::unbounded(), None, 0);
+        paint_order
+    }
+
+    /// Returns each component in the order it is painted
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &Painted<'a>> {
+        self.order.iter().map(|i| &self.entries[*i])
+    }
+
+    /// Returns where each component can be hit, which outlives the borrow of the tree
+    pub(crate) fn hit_regions(&self) -> HitRegions {
+        HitRegions {
+            entries: self
+                .entries
+                .iter()
+                .map(|painted| HitRegion {
+                    rect: painted.node.data.computed_layout.rect(),
+                    clip: painted.clip,
+                    parent: painted.parent,
+                    child_index: painted.child_index,
+                })
+                .collect(),
+            order: self.order.clone(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        node: &'a Node<Component>,
+        clip: Rect,
+        parent: Option<usize>,
+        child_index: usize,
+    ) -> usize {
+        self.entries.push(Painted {
+            node,
+            clip,
+            parent,
+            child_index,
+        });
+        self.entries.len() - 1
+    }
+
+    /// Orders the stacking context formed by the given node, which is painted first, followed by
+    /// everything within it by z-index
+    fn stack(
+        &mut self,
+        node: &'a Node<Component>,
+        clip: Rect,
+        parent: Option<usize>,
+        child_index: usize,
+    ) -> Vec<usize> {
+        let entry = self.push(node, clip, parent, child_index);
+        let mut members = Vec::new();
+        self.flatten(node, entry, clip, &mut members);
+
+        // sorting is stable, so those with the same z-index stay in the order they are defined
+        members.sort_by_key(|(z_index, _)| *z_index);
+        let mut order = vec![entry];
+        for (_, member) in members {
+            order.extend(member);
+        }
+        order
+    }
+
+    /// Collects the descendants of the given node into the stacking context it is within, where each
+    /// is either a component without a z-index, or a whole nested stacking context
+    fn flatten(
+        &mut self,
+        node: &'a Node<Component>,
+        entry: usize,
+        clip: Rect,
+        members: &mut Vec<(i32, Vec<usize>)>,
+    ) {
+        // children of a node that clips are only painted within it, as well as whatever their
+        // ancestors clip them to
+        let clip = match node.data.effective_layout.overflow.clips() {
+            true => clip.intersect(&node.data.computed_layout.rect()),
+            false => clip,
+        };
+
+        for (i, child) in node.children.iter().enumerate() {
+            match child.data.effective_layout.z_index {
+                Some(z_index) => {
+                    let context = self.stack(child, clip, Some(entry), i);
+                    members.push((z_index, context));
+                }
+                None => {
+                    let child_entry = self.push(child, clip, Some(entry), i);
+                    members.push((0, vec![child_entry]));
+                    self.flatten(child, child_entry, clip, members);
+                }
+            }
+        }
+    }
+}
+
+/// Where each component of a layer can be hit, in the order they are painted. This is kept between
+/// frames, so that the layer isn't ordered again for every pointer event
+pub(crate) struct HitRegions {
+    entries: Vec<HitRegion>,
+    order: Vec<usize>,
+}
+
+/// The box of a component, and the box it is clipped to, along with where it is in the tree as with
+/// `Painted`
+struct HitRegion {
+    rect: Rect,
+    clip: Rect,
+    parent: Option<usize>,
+    child_index: usize,
+}
+
+impl HitRegions {
+    /// Returns the path from the root to the topmost component under the given point, as the index of
+    /// each component amongst the children of its parent
+    pub(crate) fn hit_test(&self, x: f32, y: f32) -> Option<Vec<usize>> {
+        let hit = self.order.iter().rev().copied().find(|i| {
+            let region = &self.entries[*i];
+            region.clip.contains(x, y) && region.rect.contains(x, y)
+        })?;
+
+        let mut path = Vec::new();
+        let mut entry = &self.entries[hit];
+        while let Some(parent) = entry.parent {
+            path.push(entry.child_index);
+            entry = &self.entries[parent];
+        }
+        path.reverse();
+        Some(path)
+    }
+}
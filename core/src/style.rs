@@ -1,3 +1,7 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::layout::Length;
+
 pub const LIGHT: StyleSheet = StyleSheet {
     rotate: 0.0,
     opacity: 1.0,
@@ -5,7 +9,7 @@ pub const LIGHT: StyleSheet = StyleSheet {
     font_size: 16.0,
     font_weight: 600.0,
     font_colour: Colour::black(),
-    border_weight: 0.0,
+    border_weight: Length::Pixels(0.0),
     border_colour: Colour::black(),
     border_radius: BorderRadius::none(),
 };
@@ -17,11 +21,12 @@ pub const DARK: StyleSheet = StyleSheet {
     font_size: 16.0,
     font_weight: 600.0,
     font_colour: Colour::white(),
-    border_weight: 0.0,
+    border_weight: Length::Pixels(0.0),
     border_colour: Colour::white(),
     border_radius: BorderRadius::none(),
 };
 
+/// The style that every component starts from, before its own overrides and anything it inherits
 pub struct StyleSheet {
     pub rotate: f32,
     pub opacity: f32,
@@ -30,11 +35,58 @@ pub struct StyleSheet {
     pub font_size: f32,
     pub font_weight: f32,
     pub font_colour: Colour,
-    pub border_weight: f32,
+    pub border_weight: Length,
     pub border_colour: Colour,
     pub border_radius: BorderRadius,
 }
 
+/// Overrides of the style sheet for a single component, where anything not given is inherited from
+/// the parent for the font size, weight and colour, or is otherwise taken from the style sheet
+#[derive(Clone, Default)]
+pub struct Style {
+    pub rotate: Option<f32>,
+    pub opacity: Option<f32>,
+
+    pub background_colour: Option<Colour>,
+    pub font_size: Option<f32>,
+    pub font_weight: Option<f32>,
+    pub font_colour: Option<Colour>,
+    pub border_weight: Option<Length>,
+    pub border_colour: Option<Colour>,
+    pub border_radius: Option<BorderRadius>,
+}
+
+impl Style {
+    /// Returns these overrides, with anything not given taken from the other overrides
+    pub fn or(&self, other: &Style) -> Style {
+        Style {
+            rotate: self.rotate.or(other.rotate),
+            opacity: self.opacity.or(other.opacity),
+            background_colour: self.background_colour.or(other.background_colour),
+            font_size: self.font_size.or(other.font_size),
+            font_weight: self.font_weight.or(other.font_weight),
+            font_colour: self.font_colour.or(other.font_colour),
+            border_weight: self.border_weight.or(other.border_weight),
+            border_colour: self.border_colour.or(other.border_colour),
+            border_radius: self.border_radius.or(other.border_radius),
+        }
+    }
+}
+
+/// Overrides of the style of a component that apply only while it is in each state, over its other
+/// overrides. Being disabled takes precedence over every other state, then being pressed, then
+/// focused, then hovered
+#[derive(Clone, Default)]
+pub struct StateStyles {
+    pub hovered: Style,
+    pub pressed: Style,
+    pub focused: Style,
+    pub disabled: Style,
+}
+
+/// The style of a component once its overrides are cascaded over those of its ancestors and the
+/// style sheet
+#[derive(Clone, PartialEq, Debug)]
 pub struct ComputedStyle {
     pub rotate: f32,
     pub opacity: f32,
@@ -43,18 +95,65 @@ pub struct ComputedStyle {
     pub font_size: f32,
     pub font_weight: f32,
     pub font_colour: Colour,
-    pub border_weight: f32,
+    pub border_weight: Length,
     pub border_colour: Colour,
     pub border_radius: BorderRadius,
 }
 
+impl ComputedStyle {
+    /// Computes the style of a component from its overrides, the computed style of its parent, which
+    /// the root doesn't have, and the style sheet
+    pub fn cascade(style: &Style, parent: Option<&ComputedStyle>, sheet: &StyleSheet) -> Self {
+        Self {
+            rotate: style.rotate.unwrap_or(sheet.rotate),
+            opacity: style.opacity.unwrap_or(sheet.opacity),
+            background_colour: style.background_colour.unwrap_or(sheet.background_colour),
+            font_size: style
+                .font_size
+                .unwrap_or(parent.map_or(sheet.font_size, |parent| parent.font_size)),
+            font_weight: style
+                .font_weight
+                .unwrap_or(parent.map_or(sheet.font_weight, |parent| parent.font_weight)),
+            font_colour: style
+                .font_colour
+                .unwrap_or(parent.map_or(sheet.font_colour, |parent| parent.font_colour)),
+            border_weight: style.border_weight.unwrap_or(sheet.border_weight),
+            border_colour: style.border_colour.unwrap_or(sheet.border_colour),
+            border_radius: style.border_radius.unwrap_or(sheet.border_radius),
+        }
+    }
+}
+
+/// The style sheet that every component is resolved against, which can be switched at runtime
 pub enum Theme {
     Light(StyleSheet),
     Dark(StyleSheet),
     Custom(StyleSheet),
 }
 
-#[derive(Clone, Copy)]
+impl Theme {
+    pub const fn light() -> Self {
+        Theme::Light(LIGHT)
+    }
+
+    pub const fn dark() -> Self {
+        Theme::Dark(DARK)
+    }
+
+    pub fn is_dark(&self) -> bool {
+        matches!(self, Theme::Dark(_))
+    }
+
+    pub fn style_sheet(&self) -> &StyleSheet {
+        match self {
+            Theme::Light(style_sheet) | Theme::Dark(style_sheet) | Theme::Custom(style_sheet) => {
+                style_sheet
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct BorderRadius {
     pub top_right: f32,
     pub bottom_right: f32,
@@ -82,16 +181,31 @@ impl BorderRadius {
     }
 }
 
-#[derive(Clone, Copy)]
+/// A colour in sRGB, with each channel and the alpha in the range 0-1, where an alpha of 0 is fully
+/// transparent. It can also be parsed from `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` hex strings, and
+/// from `rgb()`, `rgba()`, `hsl()` and `hsla()` functions as in CSS, e.g.
+///
+/// ```
+/// # use mocha_core::style::Colour;
+/// let colour: Colour = "rgba(255, 0, 0, 0.5)".parse().unwrap();
+/// assert_eq!(colour, "hsla(0, 100%, 50%, 50%)".parse().unwrap());
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Colour {
     pub r: f32,
     pub g: f32,
     pub b: f32,
+    pub a: f32,
 }
 
 impl Colour {
+    /// An opaque colour
     pub fn new(r: f32, g: f32, b: f32) -> Self {
-        Self { r, g, b }
+        Self::rgba(r, g, b, 1.0)
+    }
+
+    pub fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
     }
 
     pub const fn white() -> Self {
@@ -99,6 +213,7 @@ impl Colour {
             r: 1.0,
             g: 1.0,
             b: 1.0,
+            a: 1.0,
         }
     }
 
@@ -107,6 +222,270 @@ impl Colour {
             r: 0.0,
             g: 0.0,
             b: 0.0,
+            a: 1.0,
+        }
+    }
+
+    pub const fn transparent() -> Self {
+        Self {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
         }
     }
+
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Converts the colour from sRGB to linear light, such as for blending, where the alpha is left as
+    /// it is
+    pub fn to_linear(&self) -> Self {
+        let linear = |channel: f32| match channel <= 0.04045 {
+            true => channel / 12.92,
+            false => ((channel + 0.055) / 1.055).powf(2.4),
+        };
+        Self::rgba(linear(self.r), linear(self.g), linear(self.b), self.a)
+    }
+
+    /// Converts the colour from linear light back to sRGB, where the alpha is left as it is
+    pub fn to_srgb(&self) -> Self {
+        let srgb = |channel: f32| match channel <= 0.0031308 {
+            true => channel * 12.92,
+            false => 1.055 * channel.powf(1.0 / 2.4) - 0.055,
+        };
+        Self::rgba(srgb(self.r), srgb(self.g), srgb(self.b), self.a)
+    }
+
+    /// Parses a hex colour without its `#`, where each channel is given by one or two digits
+    fn parse_hex(hex: &str) -> Result<Self, ParseColourError> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseColourError::InvalidHex);
+        }
+
+        let digits = match hex.len() {
+            3 | 4 => 1,
+            6 | 8 => 2,
+            _ => return Err(ParseColourError::InvalidHex),
+        };
+        let channels: Vec<f32> = (0..hex.len())
+            .step_by(digits)
+            .map(|i| {
+                let value = u8::from_str_radix(&hex[i..i + digits], 16).unwrap_or_default();
+                // a single digit is repeated, so `f` is the same as `ff`
+                match digits {
+                    1 => value as f32 / 15.0,
+                    _ => value as f32 / 255.0,
+                }
+            })
+            .collect();
+
+        Ok(Self::rgba(
+            channels[0],
+            channels[1],
+            channels[2],
+            channels.get(3).copied().unwrap_or(1.0),
+        ))
+    }
+
+    /// Converts a hue in degrees, and a saturation and lightness in the range 0-1, to a colour
+    fn from_hsl(hue: f32, saturation: f32, lightness: f32, a: f32) -> Self {
+        let amount = saturation * lightness.min(1.0 - lightness);
+        let channel = |n: f32| {
+            let k = (n + hue / 30.0).rem_euclid(12.0);
+            lightness - amount * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self::rgba(channel(0.0), channel(8.0), channel(4.0), a)
+    }
+}
+
+impl FromStr for Colour {
+    type Err = ParseColourError;
+
+    fn from_str(colour: &str) -> Result<Self, Self::Err> {
+        let colour = colour.trim();
+        if let Some(hex) = colour.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        let (function, arguments) = colour
+            .strip_suffix(')')
+            .and_then(|colour| colour.split_once('('))
+            .ok_or(ParseColourError::UnknownFormat)?;
+        let arguments: Vec<&str> = arguments.split(',').map(str::trim).collect();
+        let expected = match function.trim() {
+            "rgb" | "hsl" => 3,
+            "rgba" | "hsla" => 4,
+            _ => return Err(ParseColourError::UnknownFormat),
+        };
+        if arguments.len() != expected {
+            return Err(ParseColourError::ArgumentCount {
+                expected,
+                found: arguments.len(),
+            });
+        }
+        let a = match arguments.get(3) {
+            Some(alpha) => parse_fraction(alpha, 1.0)?,
+            None => 1.0,
+        };
+
+        match function.trim() {
+            "rgb" | "rgba" => Ok(Self::rgba(
+                parse_fraction(arguments[0], 255.0)?,
+                parse_fraction(arguments[1], 255.0)?,
+                parse_fraction(arguments[2], 255.0)?,
+                a,
+            )),
+            _ => {
+                let hue = arguments[0].strip_suffix("deg").unwrap_or(arguments[0]);
+                // any angle is a hue, but not NaN or infinity
+                let hue = hue
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|hue| hue.is_finite())
+                    .ok_or_else(|| ParseColourError::InvalidArgument(arguments[0].to_string()))?;
+                Ok(Self::from_hsl(
+                    hue,
+                    parse_percentage(arguments[1])?,
+                    parse_percentage(arguments[2])?,
+                    a,
+                ))
+            }
+        }
+    }
+}
+
+/// Parses an argument of a colour function as a fraction of the given maximum, or as a percentage
+fn parse_fraction(argument: &str, max: f32) -> Result<f32, ParseColourError> {
+    if argument.ends_with('%') {
+        return parse_percentage(argument);
+    }
+
+    let value: f32 = argument
+        .parse()
+        .map_err(|_| ParseColourError::InvalidArgument(argument.to_string()))?;
+    match (0.0..=max).contains(&value) {
+        true => Ok(value / max),
+        false => Err(ParseColourError::OutOfRange(argument.to_string())),
+    }
+}
+
+/// Parses an argument of a colour function that must be a percentage, as a fraction
+fn parse_percentage(argument: &str) -> Result<f32, ParseColourError> {
+    let percentage: f32 = argument
+        .strip_suffix('%')
+        .and_then(|percentage| percentage.trim().parse().ok())
+        .ok_or_else(|| ParseColourError::InvalidArgument(argument.to_string()))?;
+    match (0.0..=100.0).contains(&percentage) {
+        true => Ok(percentage / 100.0),
+        false => Err(ParseColourError::OutOfRange(argument.to_string())),
+    }
+}
+
+/// Why a string couldn't be parsed as a `Colour`
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParseColourError {
+    /// The string is neither a hex colour, nor one of the `rgb`, `rgba`, `hsl` and `hsla` functions
+    UnknownFormat,
+    /// The hex colour isn't 3, 4, 6 or 8 hexadecimal digits
+    InvalidHex,
+    /// The function was given the wrong number of arguments
+    ArgumentCount { expected: usize, found: usize },
+    /// The argument isn't a number, or isn't a percentage where one is needed
+    InvalidArgument(String),
+    /// The argument is outside the range of the channel it is given for
+    OutOfRange(String),
+}
+
+impl fmt::Display for ParseColourError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColourError::UnknownFormat => write!(f, "unknown colour format"),
+            ParseColourError::InvalidHex => {
+                write!(f, "hex colours must be 3, 4, 6 or 8 hex digits")
+            }
+            ParseColourError::ArgumentCount { expected, found } => {
+                write!(f, "expected {expected} arguments, found {found}")
+            }
+            ParseColourError::InvalidArgument(argument) => {
+                write!(f, "invalid argument `{argument}`")
+            }
+            ParseColourError::OutOfRange(argument) => write!(f, "`{argument}` is out of range"),
+        }
+    }
+}
+
+impl Error for ParseColourError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_colours() {
+        let parse = |colour: &str| colour.parse::<Colour>();
+
+        assert_eq!(parse("#f00"), Ok(Colour::new(1.0, 0.0, 0.0)));
+        assert_eq!(parse("#0000"), Ok(Colour::transparent()));
+        assert_eq!(parse("#FFFFFF"), Ok(Colour::white()));
+        assert_eq!(parse("#000000ff"), Ok(Colour::black()));
+        assert_eq!(parse("rgb(255, 0, 51)"), Ok(Colour::new(1.0, 0.0, 0.2)));
+        assert_eq!(
+            parse(" rgba(0%, 100%, 0%, 0.25) "),
+            Ok(Colour::rgba(0.0, 1.0, 0.0, 0.25))
+        );
+        assert_eq!(
+            parse("hsl(120deg, 100%, 50%)"),
+            Ok(Colour::new(0.0, 1.0, 0.0))
+        );
+        assert_eq!(
+            parse("hsla(-120, 100%, 50%, 50%)"),
+            Ok(Colour::rgba(0.0, 0.0, 1.0, 0.5))
+        );
+        assert_eq!(parse("hsl(0, 0%, 100%)"), Ok(Colour::white()));
+
+        assert_eq!(parse("red"), Err(ParseColourError::UnknownFormat));
+        assert_eq!(parse("#ff00"), Ok(Colour::rgba(1.0, 1.0, 0.0, 0.0)));
+        assert_eq!(parse("#ff000"), Err(ParseColourError::InvalidHex));
+        assert_eq!(parse("#+f0"), Err(ParseColourError::InvalidHex));
+        assert_eq!(
+            parse("rgb(1, 2, 3, 4)"),
+            Err(ParseColourError::ArgumentCount {
+                expected: 3,
+                found: 4
+            })
+        );
+        assert_eq!(
+            parse("rgb(256, 0, 0)"),
+            Err(ParseColourError::OutOfRange("256".to_string()))
+        );
+        assert_eq!(
+            parse("hsl(0, 50, 50%)"),
+            Err(ParseColourError::InvalidArgument("50".to_string()))
+        );
+        assert_eq!(
+            parse("hsl(nan, 50%, 50%)"),
+            Err(ParseColourError::InvalidArgument("nan".to_string()))
+        );
+        assert_eq!(
+            parse("hsl(inf, 50%, 50%)"),
+            Err(ParseColourError::InvalidArgument("inf".to_string()))
+        );
+    }
+
+    #[test]
+    fn convert_linear() {
+        let colour = Colour::rgba(0.0, 0.5, 1.0, 0.5);
+        let linear = colour.to_linear();
+        assert_eq!(linear.r, 0.0);
+        assert!((linear.g - 0.21404).abs() < 1e-4);
+        assert_eq!(linear.b, 1.0);
+        assert_eq!(linear.a, 0.5);
+
+        let srgb = linear.to_srgb();
+        assert!((srgb.g - 0.5).abs() < 1e-5);
+        assert_eq!(srgb.a, 0.5);
+    }
 }
@@ -6,7 +6,10 @@ pub trait Window {
     fn set_title(&mut self, title: &str);
     fn get_title(&self) -> &str;
     fn set_size(&mut self, width: u32, height: u32);
+    /// Returns the size of the drawable area of the window in physical pixels
     fn get_size(&self) -> (u32, u32);
+    /// Returns the number of physical pixels per logical pixel, e.g. `2.0` on most HiDPI displays
+    fn get_scale_factor(&self) -> f32;
     fn set_resizable(&mut self, resizable: bool);
     fn get_resizable(&self) -> bool;
     fn set_maximised(&mut self, maximised: bool);
@@ -4,19 +4,22 @@ uniform mat4 u_projection;
 
 in vec2  a_position;
 in vec2  a_dimensions;
-in vec3  a_colour;
+in vec4  a_colour;
 in float a_opacity;
-in vec3  a_borderColour;
+in vec4  a_borderColour;
 in vec4  a_borderRadii;
 in float a_borderWeight;
+in vec4  a_clip;
 
+out vec2  v_point;
 out vec2  v_centreToPoint;
 out vec2  v_halfDimensions;
-out vec3  v_colour;
+out vec4  v_colour;
 out float v_opacity;
-out vec3  v_borderColour;
+out vec4  v_borderColour;
 out vec4  v_borderRadii;
 out float v_borderWeight;
+out vec4  v_clip;
 
 vec2 CORNERS[4] = vec2[](
     vec2(0.0, 0.0),
@@ -30,6 +33,7 @@ void main() {
     vec2 current = (a_position + a_dimensions * CORNERS[gl_VertexID]) * vec2(1.0, -1.0);
     vec2 centre = (a_position + a_dimensions * 0.5) * vec2(1.0, -1.0);
 
+    v_point = a_position + a_dimensions * CORNERS[gl_VertexID];
     v_centreToPoint = current - centre;
     v_halfDimensions = a_dimensions * 0.5;
     v_colour = a_colour;
@@ -37,6 +41,7 @@ void main() {
     v_borderColour = a_borderColour;
     v_borderRadii = a_borderRadii;
     v_borderWeight = a_borderWeight;
+    v_clip = a_clip;
 
     gl_Position = u_projection * vec4(current, 0.0, 1.0);
 }
@@ -52,13 +57,18 @@ void main() {
     #endif
 #endif
 
+in vec2  v_point;
 in vec2  v_centreToPoint;
 in vec2  v_halfDimensions;
-in vec3  v_colour;
+in vec4  v_colour;
 in float v_opacity;
-in vec3  v_borderColour;
+in vec4  v_borderColour;
 in vec4  v_borderRadii;
 in float v_borderWeight;
+in vec4  v_clip;
+
+// the number of physical pixels to each logical pixel that positions and sizes are given in
+uniform float u_scaleFactor;
 
 out vec4 fragColour;
 
@@ -71,13 +81,22 @@ float signedDistanceRoundRect(vec2 pos, vec2 size, vec4 radii) {
 }
 
 void main() {
+    // `v_clip` is the top-left position and dimensions of the box outside of which nothing is drawn
+    if (any(lessThan(v_point, v_clip.xy)) || any(greaterThanEqual(v_point, v_clip.xy + v_clip.zw))) {
+        discard;
+    }
+
+    // edges are anti-aliased over a single physical pixel, whatever the scale factor
+    float pixel = 1.0 / u_scaleFactor;
+
     float outerEdge = signedDistanceRoundRect(v_centreToPoint, v_halfDimensions, v_borderRadii);
-    float alpha = 1.0 - smoothstep(0.0, 1.0, outerEdge);
+    float alpha = 1.0 - smoothstep(0.0, pixel, outerEdge);
 
     float innerEdge = outerEdge + v_borderWeight;
-    float borderMix = smoothstep(0.0, 0.67, innerEdge);
+    float borderMix = smoothstep(0.0, 0.67 * pixel, innerEdge);
 
-    vec3 finalColour = mix(v_colour, v_borderColour, borderMix);
+    // the background and border each have their own alpha, which the opacity applies to as a whole
+    vec4 finalColour = mix(v_colour, v_borderColour, borderMix);
 
-    fragColour = vec4(finalColour, alpha * v_opacity);
+    fragColour = vec4(finalColour.rgb, finalColour.a * alpha * v_opacity);
 }
\ No newline at end of file
@@ -1,14 +1,24 @@
 use std::num::NonZeroU32;
 
 use glutin::surface::GlSurface;
-use mocha_core::{Renderer, component::{Layer, Component}};
-use winit::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
+use mocha_core::component::{Layer, Component};
+use winit::dpi::PhysicalSize;
+use mocha_core::event::{Key, KeyboardEvent, MouseButton, PointerEvent};
+use winit::event::{
+    DeviceEvent, ElementState, Event, ModifiersState, MouseScrollDelta, VirtualKeyCode,
+    WindowEvent,
+};
 
 use crate::{graphics::renderer::GlRenderer, window::window::GlWindow};
 
+/// The number of pixels scrolled by a single line of a mouse wheel
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
 pub fn execute() {
     let mut app = mocha_core::Application::<GlRenderer, GlWindow>::new();
     let event_loop = app.window.event_loop.take().unwrap();
+    let mut cursor_position = (0.0, 0.0);
+    let mut modifiers = ModifiersState::empty();
 
     event_loop.run(move |event, _, control_flow| {
         control_flow.set_wait();
@@ -27,46 +37,84 @@ pub fn execute() {
             },
             Event::WindowEvent { event, .. } => match event {
                 WindowEvent::MouseInput { state, button, .. } => {
-                    // self.input.mouse.handle_input(button, state);
+                    let button = match button {
+                        winit::event::MouseButton::Left => MouseButton::Left,
+                        winit::event::MouseButton::Right => MouseButton::Right,
+                        winit::event::MouseButton::Middle => MouseButton::Middle,
+                        winit::event::MouseButton::Other(_) => MouseButton::Other,
+                    };
+                    app.handle_event(mocha_core::Event::PointerEvent(PointerEvent::MouseInput {
+                        x: cursor_position.0,
+                        y: cursor_position.1,
+                        button,
+                        pressed: state == ElementState::Pressed,
+                    }));
                 }
 
+                WindowEvent::ModifiersChanged(state) => modifiers = state,
+
                 WindowEvent::KeyboardInput { input, .. } => {
-                    if let Some(key) = input.virtual_keycode {
-                        match key {
-                            VirtualKeyCode::F1 => {
-                            }
-                            _ => {}
-                        }
-                    }
+                    let key = match input.virtual_keycode {
+                        Some(VirtualKeyCode::Tab) => Key::Tab,
+                        Some(VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter) => Key::Enter,
+                        Some(VirtualKeyCode::Space) => Key::Space,
+                        _ => Key::Other,
+                    };
+                    app.handle_event(mocha_core::Event::KeyboardEvent(
+                        KeyboardEvent::KeyboardInput {
+                            key,
+                            pressed: input.state == ElementState::Pressed,
+                            shift: modifiers.shift(),
+                        },
+                    ));
                 }
 
                 WindowEvent::CursorEntered { .. } => {
-                    // self.input.mouse.on_window = true;
+                    app.handle_event(mocha_core::Event::PointerEvent(PointerEvent::MouseEntered));
                 }
 
                 WindowEvent::CursorLeft { .. } => {
-                    // self.input.mouse.on_window = false;
+                    app.handle_event(mocha_core::Event::PointerEvent(PointerEvent::MouseExited));
                 }
 
                 WindowEvent::CursorMoved { position, .. } => {
-                    // self.input.mouse.pos_x = position.x;
-                    // self.input.mouse.pos_y = position.y;
+                    let position = position.to_logical::<f32>(app.window.scale_factor as f64);
+                    cursor_position = (position.x, position.y);
+                    app.handle_event(mocha_core::Event::PointerEvent(PointerEvent::MouseMoved {
+                        x: cursor_position.0,
+                        y: cursor_position.1,
+                    }));
                 }
 
-                WindowEvent::Resized(ref size) => {
-                    if size.width != 0 && size.height != 0 {
-                        // Some platforms like EGL require resizing GL surface to update the size
-                        // Notable platforms here are Wayland and macOS, other don't require it
-                        // and the function is no-op, but it's wise to resize it for portability
-                        // reasons.
-                        app.window.surface.resize(
-                            &app.window.gl_context,
-                            NonZeroU32::new(size.width).unwrap(),
-                            NonZeroU32::new(size.height).unwrap(),
-                        );
-                        app.renderer.update_viewport(size.width, size.height);
-                    }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let (delta_x, delta_y) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => {
+                            (x * SCROLL_LINE_HEIGHT, y * SCROLL_LINE_HEIGHT)
+                        }
+                        MouseScrollDelta::PixelDelta(delta) => {
+                            let delta = delta.to_logical::<f32>(app.window.scale_factor as f64);
+                            (delta.x, delta.y)
+                        }
+                    };
+
+                    app.handle_event(mocha_core::Event::PointerEvent(
+                        PointerEvent::MouseWheel {
+                            x: cursor_position.0,
+                            y: cursor_position.1,
+                            delta_x,
+                            delta_y,
+                        },
+                    ));
                 }
+
+                WindowEvent::Resized(size) => {
+                    let scale_factor = app.window.scale_factor;
+                    resize(&mut app, size, scale_factor)
+                }
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                } => resize(&mut app, *new_inner_size, scale_factor as f32),
                 WindowEvent::Moved(_) => {
                     // self.context.being_moved = true;
                 }
@@ -74,7 +122,9 @@ pub fn execute() {
                 _ => {}
             },
             Event::MainEventsCleared => {
-                app.window.window.request_redraw();
+                if app.needs_redraw() {
+                    app.window.window.request_redraw();
+                }
             }
 
             Event::RedrawRequested(_) => {
@@ -93,3 +143,37 @@ pub fn execute() {
         }
     });
 }
+
+/// Resizes the surface to the given size in physical pixels, and passes the new size and scale factor
+/// on to the application
+fn resize(
+    app: &mut mocha_core::Application<GlRenderer, GlWindow>,
+    size: PhysicalSize<u32>,
+    scale_factor: f32,
+) {
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+
+    // Some platforms like EGL require resizing GL surface to update the size
+    // Notable platforms here are Wayland and macOS, other don't require it
+    // and the function is no-op, but it's wise to resize it for portability
+    // reasons.
+    app.window.surface.resize(
+        &app.window.gl_context,
+        NonZeroU32::new(size.width).unwrap(),
+        NonZeroU32::new(size.height).unwrap(),
+    );
+    app.window.width = size.width;
+    app.window.height = size.height;
+    app.window.scale_factor = scale_factor;
+
+    app.handle_event(mocha_core::Event::WindowEvent(
+        mocha_core::event::WindowEvent::Resized {
+            width: size.width,
+            height: size.height,
+            scale_factor,
+        },
+    ));
+    app.window.window.request_redraw();
+}
@@ -17,6 +17,7 @@ pub struct QuadPipeline {
     pub program: Program,
     vertex_array: VertexArray,
     transform_loc: glow::UniformLocation,
+    scale_factor_loc: glow::UniformLocation,
 
     buffer: Vec<mocha_core::Quad>,
 }
@@ -28,17 +29,22 @@ impl QuadPipeline {
             gl.get_uniform_location(program.handle, "u_projection")
                 .unwrap()
         };
+        let scale_factor_loc = unsafe {
+            gl.get_uniform_location(program.handle, "u_scaleFactor")
+                .unwrap()
+        };
         let vertex_array = VertexArray::new(
             gl,
             vec![BufferLayout::new(
                 vec![
                     BufferElement::new(ShaderDataType::Float2, "positions"),
                     BufferElement::new(ShaderDataType::Float2, "dimensions"),
-                    BufferElement::new(ShaderDataType::Float3, "colours"),
+                    BufferElement::new(ShaderDataType::Float4, "colours"),
                     BufferElement::new(ShaderDataType::Float1, "opacity"),
-                    BufferElement::new(ShaderDataType::Float3, "border_colours"),
+                    BufferElement::new(ShaderDataType::Float4, "border_colours"),
                     BufferElement::new(ShaderDataType::Float4, "border_radii"),
                     BufferElement::new(ShaderDataType::Float1, "border_weights"),
+                    BufferElement::new(ShaderDataType::Float4, "clips"),
                 ],
                 QUAD_BUFFER_SIZE,
                 1,
@@ -62,6 +68,7 @@ impl QuadPipeline {
             program,
             vertex_array,
             transform_loc,
+            scale_factor_loc,
             buffer: Vec::new(),
         }
     }
@@ -72,6 +79,13 @@ impl QuadPipeline {
         }
     }
 
+    /// Sets the number of physical pixels to each logical pixel, which edges are anti-aliased over
+    pub fn update_scale_factor(&self, gl: &glow::Context, scale_factor: f32) {
+        unsafe {
+            gl.uniform_1_f32(Some(&self.scale_factor_loc), scale_factor);
+        }
+    }
+
     pub fn buffer(&mut self, quad: mocha_core::Quad) {
         self.buffer.push(quad);
     }
@@ -52,15 +52,18 @@ impl mocha_core::Renderer for GlRenderer {
 
         let quad_pipeline = QuadPipeline::new(&gl);
         quad_pipeline.update_transform(&gl, Mat4f::identity());
+        quad_pipeline.update_scale_factor(&gl, 1.0);
 
         Self { gl, quad_pipeline }
     }
 
-    fn update_viewport(&self, width: u32, height: u32) {
+    fn update_viewport(&self, width: u32, height: u32, scale_factor: f32) {
+        // quads are given in logical pixels, so the projection covers the logical size of the viewport
         self.quad_pipeline.update_transform(
             &self.gl,
-            Mat4f::orthographic_2d(width as f32, height as f32),
+            Mat4f::orthographic_2d(width as f32 / scale_factor, height as f32 / scale_factor),
         );
+        self.quad_pipeline.update_scale_factor(&self.gl, scale_factor);
 
         unsafe {
             self.gl.viewport(0, 0, width as i32, height as i32);
@@ -31,8 +31,10 @@ pub struct GlWindow {
     pub(crate) resizable: bool,
     pub(crate) maximised: bool,
     pub(crate) fullscreen: Option<Fullscreen>,
+    /// the size of the drawable area in physical pixels
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) scale_factor: f32,
 }
 
 impl GlWindow {
@@ -42,6 +44,7 @@ impl GlWindow {
         let (window, gl_config) = Self::build_window(window_builder, display_config, &event_loop);
 
         let (width, height): (u32, u32) = window.inner_size().into();
+        let scale_factor = window.scale_factor() as f32;
         let raw_window_handle = window.raw_window_handle();
         let attrs = SurfaceAttributesBuilder::<WindowSurface>::new().build(
             raw_window_handle,
@@ -70,6 +73,7 @@ impl GlWindow {
             fullscreen: None,
             width,
             height,
+            scale_factor,
         }
     }
 
@@ -152,8 +156,6 @@ impl mocha_core::Window for GlWindow {
 
         let mut gl_window = GlWindow::new(window_builder, display_config);
         gl_window.title = config.title.to_string();
-        gl_window.width = config.size.0;
-        gl_window.height = config.size.1;
         gl_window.resizable = config.resizable;
         gl_window.maximised = config.maximised;
 
@@ -209,6 +211,10 @@ impl mocha_core::Window for GlWindow {
         (self.width, self.height)
     }
 
+    fn get_scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
     fn set_resizable(&mut self, resizable: bool) {
         if self.resizable == resizable {
             return;